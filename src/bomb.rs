@@ -0,0 +1,174 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{FromEnemy, Laser},
+    config::GameConfig,
+    player_laser_hit_enemy_system,
+    state::GameState,
+    GameClock, GameSystem, PlayerState,
+};
+
+pub const BOMB_KEY: KeyCode = KeyCode::B;
+/// seconds the full-screen flash takes to fade out
+const FLASH_DURATION: f32 = 0.4;
+const FLASH_ALPHA: f32 = 0.8;
+
+/// Sent when the player sets off a smart bomb, the enemy hit systems take
+/// `damage` off everything they handle.
+pub struct BombDetonated {
+    pub damage: u32,
+}
+
+/// Full-screen white flash, fading out.
+#[derive(Component)]
+struct BombFlash(Timer);
+
+pub struct BombPlugin;
+
+impl Plugin for BombPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<BombDetonated>()
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    // before the hit systems reading its `BombDetonated`
+                    .with_system(
+                        bomb_system
+                            .label(GameSystem::Collision)
+                            .after(GameSystem::Movement)
+                            .before(player_laser_hit_enemy_system),
+                    )
+                    .with_system(
+                        bomb_flash_system
+                            .label(GameSystem::Cleanup)
+                            .after(GameSystem::Collision),
+                    ),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::Playing).with_system(bomb_cleanup_system),
+            );
+    }
+}
+
+fn bomb_system(
+    mut commands: Commands,
+    kb: Res<Input<KeyCode>>,
+    clock: Res<GameClock>,
+    config: Res<GameConfig>,
+    mut player_state: ResMut<PlayerState>,
+    mut detonated: EventWriter<BombDetonated>,
+    laser_query: Query<Entity, (With<Laser>, With<FromEnemy>)>,
+) {
+    if !kb.just_pressed(BOMB_KEY) || !player_state.on || player_state.bombs == 0 {
+        return;
+    }
+
+    let bomb = &config.player.bomb;
+    player_state.bombs -= 1;
+    player_state.invulnerable_until = clock.0 + bomb.invulnerable_duration;
+
+    // clear the enemy lasers, the enemies themselves are left to the hit systems
+    for entity in laser_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    detonated.send(BombDetonated {
+        damage: bomb.damage,
+    });
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                position_type: PositionType::Absolute,
+                ..Default::default()
+            },
+            color: Color::rgba(1., 1., 1., FLASH_ALPHA).into(),
+            ..Default::default()
+        })
+        .insert(BombFlash(Timer::from_seconds(FLASH_DURATION, false)));
+}
+
+fn bomb_flash_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut BombFlash, &mut UiColor)>,
+) {
+    for (entity, mut flash, mut color) in query.iter_mut() {
+        if flash.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        } else {
+            color.0.set_a(FLASH_ALPHA * (1. - flash.0.percent()));
+        }
+    }
+}
+
+fn bomb_cleanup_system(mut commands: Commands, query: Query<Entity, With<BombFlash>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::FromPlayer;
+
+    fn world(bombs: u32) -> World {
+        let config = GameConfig::default();
+        let mut player_state = PlayerState::new(&config.player);
+        player_state.on = true;
+        player_state.bombs = bombs;
+        let mut kb = Input::<KeyCode>::default();
+        kb.press(BOMB_KEY);
+
+        let mut world = World::new();
+        world.insert_resource(kb);
+        world.insert_resource(GameClock::default());
+        world.insert_resource(config);
+        world.insert_resource(player_state);
+        world.insert_resource(Events::<BombDetonated>::default());
+        world
+    }
+
+    fn damage_sent(world: &World) -> Vec<u32> {
+        let events = world.resource::<Events<BombDetonated>>();
+        events
+            .get_reader()
+            .iter(events)
+            .map(|bomb| bomb.damage)
+            .collect()
+    }
+
+    #[test]
+    fn bomb_clears_enemy_lasers_and_spends_one() {
+        let mut world = world(2);
+        let enemy_lasers: Vec<Entity> = (0..3)
+            .map(|_| world.spawn().insert(Laser).insert(FromEnemy).id())
+            .collect();
+        let player_laser = world.spawn().insert(Laser).insert(FromPlayer).id();
+
+        SystemStage::single_threaded()
+            .with_system(bomb_system)
+            .run(&mut world);
+
+        for laser in enemy_lasers {
+            assert!(world.get_entity(laser).is_none());
+        }
+        assert!(world.get_entity(player_laser).is_some());
+        assert_eq!(world.resource::<PlayerState>().bombs, 1);
+        let damage = world.resource::<GameConfig>().player.bomb.damage;
+        assert_eq!(damage_sent(&world), vec![damage]);
+    }
+
+    #[test]
+    fn no_bomb_without_stock() {
+        let mut world = world(0);
+        let enemy_laser = world.spawn().insert(Laser).insert(FromEnemy).id();
+
+        SystemStage::single_threaded()
+            .with_system(bomb_system)
+            .run(&mut world);
+
+        assert!(world.get_entity(enemy_laser).is_some());
+        assert!(damage_sent(&world).is_empty());
+    }
+}
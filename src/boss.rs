@@ -0,0 +1,464 @@
+use std::{collections::HashSet, f32::consts::TAU};
+
+use bevy::{math::Vec3Swizzles, prelude::*, sprite::collide_aabb::collide};
+
+use crate::{
+    bomb::BombDetonated,
+    components::{
+        Damage, Enemy, ExplosionToSpawn, FromPlayer, Health, Laser, Moveable, Piercing, SpriteSize,
+        Velocity,
+    },
+    config::GameConfig,
+    enemy_laser_hit_player_system, moveable_system,
+    pattern::Emitter,
+    player_laser_hit_enemy_system,
+    rng::RngSystem,
+    score::TargetDestroyed,
+    state::GameState,
+    wave::{
+        script::{BossMovement, BossPhase, BossTemplate},
+        BossStage, WaveProgress,
+    },
+    EnemyCount, GameSystem, GameTextures, WinSize,
+};
+
+/// top speed of the body, as a `Velocity`
+const BOSS_SPEED_MAX: f32 = 0.4;
+/// resting position, below the top of the window
+const BOSS_HOME_MARGIN: f32 = 170.;
+/// from the body to where its lasers leave
+const BOSS_GUN_OFFSET: Vec2 = Vec2::new(0., -40.);
+/// explosions spread over the body when the boss goes down
+const DEFEAT_EXPLOSIONS: usize = 5;
+
+// region: --- Boss Components
+
+/// Body of the boss, carrying its hit zones around.
+#[derive(Component)]
+pub struct Boss {
+    template: BossTemplate,
+    /// resting position, the phase movement is relative to it
+    home: Vec2,
+    phase: usize,
+    /// seconds since the current phase started
+    clock: f32,
+    fire_timer: Timer,
+}
+
+impl Boss {
+    /// Phase to move on to at `fraction` of the total hit points, never back
+    /// to an earlier one.
+    fn next_phase(&self, fraction: f32) -> Option<usize> {
+        let phase = self
+            .template
+            .phases
+            .iter()
+            .rposition(|phase| fraction <= phase.below)
+            .unwrap_or(0);
+        (phase > self.phase).then_some(phase)
+    }
+
+    fn enter_phase(&mut self, phase: usize) {
+        self.phase = phase;
+        self.clock = 0.;
+        self.fire_timer = fire_timer(&self.template.phases[phase]);
+    }
+}
+
+fn fire_timer(phase: &BossPhase) -> Timer {
+    // a phase with no fire rate is skipped in `boss_fire_system`
+    let interval = if phase.fire_rate > 0. {
+        1. / phase.fire_rate
+    } else {
+        1.
+    };
+    Timer::from_seconds(interval, true)
+}
+
+/// Independently destructible hit zone, following the `Boss` body.
+#[derive(Component)]
+pub struct BossPart {
+    offset: Vec2,
+    max_hp: u32,
+    points: u32,
+}
+
+// endregion: --- Boss Components
+
+/// Hit points of the boss being fought, only present during the fight.
+pub struct BossHealth {
+    pub hp: u32,
+    pub total: u32,
+}
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(GameState::Playing)
+                // after the enemies, which read the wave's progress to fire
+                .with_system(
+                    boss_spawn_system
+                        .label(GameSystem::Spawn)
+                        .after(RngSystem::EnemyFire),
+                )
+                // sharing the `Emitter` of its guns with the enemies' fire
+                .with_system(
+                    boss_fire_system
+                        .label(GameSystem::Spawn)
+                        .after(GameSystem::Input)
+                        .before(RngSystem::EnemyFire),
+                )
+                .with_system(
+                    boss_movement_system
+                        .label(GameSystem::Movement)
+                        .after(GameSystem::Spawn)
+                        .before(moveable_system),
+                )
+                .with_system(
+                    boss_part_follow_system
+                        .label(GameSystem::Movement)
+                        .after(moveable_system),
+                )
+                .with_system(
+                    boss_hit_system
+                        .label(GameSystem::Collision)
+                        .after(player_laser_hit_enemy_system)
+                        .before(enemy_laser_hit_player_system),
+                ),
+        )
+        .add_system_set(SystemSet::on_exit(GameState::Playing).with_system(boss_cleanup_system));
+    }
+}
+
+fn boss_spawn_system(
+    mut commands: Commands,
+    mut progress: ResMut<WaveProgress>,
+    enemy_count: Res<EnemyCount>,
+    config: Res<GameConfig>,
+    game_textures: Res<GameTextures>,
+    win_size: Res<WinSize>,
+) {
+    if !progress.spawn_done() || enemy_count.0 > 0 {
+        return;
+    }
+    let template = match &progress.boss {
+        BossStage::Waiting(template) => template.clone(),
+        _ => return,
+    };
+
+    // enters from above, then settles at its home position
+    let start = Vec3::new(0., win_size.h / 2. + 200., 9.);
+    let home = Vec2::new(0., win_size.h / 2. - BOSS_HOME_MARGIN);
+    let scale = config.sprite_scale * template.scale;
+    let (r, g, b) = template.color;
+    let total = template.parts.iter().map(|part| part.hp).sum();
+
+    for part in template.parts.iter() {
+        let offset = Vec2::new(part.offset.0, part.offset.1);
+        let (r, g, b) = part.color;
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgb(r, g, b),
+                    custom_size: Some(Vec2::new(part.size.0, part.size.1)),
+                    ..Default::default()
+                },
+                transform: Transform {
+                    translation: start + offset.extend(2.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(Enemy)
+            .insert(BossPart {
+                offset,
+                max_hp: part.hp,
+                points: part.points,
+            })
+            .insert(Health(part.hp))
+            .insert(SpriteSize::from(part.size));
+    }
+
+    let fire_timer = fire_timer(&template.phases[0]);
+    let pattern = template.phases[0].pattern.clone();
+    commands
+        .spawn_bundle(SpriteBundle {
+            texture: game_textures.boss.clone(),
+            sprite: Sprite {
+                color: Color::rgb(r, g, b),
+                ..Default::default()
+            },
+            transform: Transform {
+                translation: start,
+                scale: Vec3::new(scale, scale, 1.),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(Enemy)
+        .insert(Boss {
+            template,
+            home,
+            phase: 0,
+            clock: 0.,
+            fire_timer,
+        })
+        .insert(Emitter::new(pattern, BOSS_GUN_OFFSET))
+        .insert(Velocity { x: 0., y: 0. })
+        .insert(Moveable {
+            auto_despawn: false,
+        });
+
+    commands.insert_resource(BossHealth { hp: total, total });
+    progress.boss = BossStage::Fighting;
+}
+
+fn boss_movement_system(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    mut query: Query<(&mut Boss, &Transform, &mut Velocity)>,
+) {
+    let delta = time.delta_seconds();
+    if delta == 0. {
+        return;
+    }
+
+    for (mut boss, tf, mut velocity) in query.iter_mut() {
+        boss.clock += delta;
+        let cycle = |period: f32| TAU * boss.clock / period;
+        let offset = match boss.template.phases[boss.phase].movement {
+            BossMovement::Hover => Vec2::ZERO,
+            BossMovement::Sweep { width, period } => Vec2::new(width * cycle(period).sin(), 0.),
+            BossMovement::Circle { radius, period } => {
+                let angle = cycle(period);
+                Vec2::new(radius * angle.cos(), radius * angle.sin())
+            }
+        };
+
+        // head for the point on the path, no faster than the top speed
+        let target = boss.home + offset;
+        let step = (target - tf.translation.xy()) / (delta * config.base_speed);
+        let step = step.clamp_length_max(BOSS_SPEED_MAX);
+        (velocity.x, velocity.y) = (step.x, step.y);
+    }
+}
+
+fn boss_part_follow_system(
+    boss_query: Query<&Transform, With<Boss>>,
+    mut part_query: Query<(&BossPart, &mut Transform), Without<Boss>>,
+) {
+    if let Ok(boss_tf) = boss_query.get_single() {
+        for (part, mut tf) in part_query.iter_mut() {
+            let position = boss_tf.translation.xy() + part.offset;
+            (tf.translation.x, tf.translation.y) = (position.x, position.y);
+        }
+    }
+}
+
+fn boss_fire_system(time: Res<Time>, mut query: Query<(&mut Boss, &mut Emitter)>) {
+    for (mut boss, mut emitter) in query.iter_mut() {
+        if !boss.fire_timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+        if boss.template.phases[boss.phase].fire_rate > 0. {
+            emitter.trigger();
+        }
+    }
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn boss_hit_system(
+    mut commands: Commands,
+    mut progress: ResMut<WaveProgress>,
+    mut boss_health: Option<ResMut<BossHealth>>,
+    mut destroyed: EventWriter<TargetDestroyed>,
+    mut bombs: EventReader<BombDetonated>,
+    mut laser_query: Query<
+        (
+            Entity,
+            &Transform,
+            &SpriteSize,
+            &Damage,
+            Option<&mut Piercing>,
+        ),
+        (With<Laser>, With<FromPlayer>),
+    >,
+    mut part_query: Query<(
+        Entity,
+        &Transform,
+        &SpriteSize,
+        &BossPart,
+        &mut Health,
+        &mut Sprite,
+    )>,
+    mut boss_query: Query<(Entity, &mut Boss, &Transform), Without<BossPart>>,
+) {
+    // damage dealt to each part this frame, a smart bomb hits them all
+    let mut hits: Vec<(Entity, u32)> = Vec::new();
+    let bomb_damage: u32 = bombs.iter().map(|bomb| bomb.damage).sum();
+    if bomb_damage > 0 {
+        hits.extend(part_query.iter().map(|(entity, ..)| (entity, bomb_damage)));
+    }
+
+    let mut despawned_lasers: HashSet<Entity> = HashSet::new();
+    for (laser_entity, laser_tf, laser_size, damage, mut piercing) in laser_query.iter_mut() {
+        let laser_scale = laser_tf.scale.xy();
+
+        for (part_entity, part_tf, part_size, ..) in part_query.iter() {
+            if despawned_lasers.contains(&laser_entity) {
+                continue;
+            }
+
+            let collision = collide(
+                laser_tf.translation,
+                laser_size.0 * laser_scale,
+                part_tf.translation,
+                part_size.0 * part_tf.scale.xy(),
+            );
+            if collision.is_none() {
+                continue;
+            }
+
+            if let Some(piercing) = piercing.as_mut() {
+                // goes on, hitting each part once
+                if !piercing.0.insert(part_entity) {
+                    continue;
+                }
+            } else {
+                commands.entity(laser_entity).despawn();
+                despawned_lasers.insert(laser_entity);
+            }
+            hits.push((part_entity, damage.0));
+        }
+    }
+
+    let mut despawned_entities: HashSet<Entity> = HashSet::new();
+    for (part_entity, damage) in hits {
+        let (_, part_tf, _, part, mut health, mut sprite) = match part_query.get_mut(part_entity) {
+            Ok(part) => part,
+            Err(_) => continue,
+        };
+        // already destroyed this frame
+        if health.0 == 0 {
+            continue;
+        }
+
+        let taken = damage.min(health.0);
+        health.0 -= taken;
+        if let Some(boss_health) = boss_health.as_mut() {
+            boss_health.hp = boss_health.hp.saturating_sub(taken);
+        }
+
+        if health.0 > 0 {
+            // fades as it takes damage
+            let alpha = 0.3 + 0.7 * health.0 as f32 / part.max_hp as f32;
+            sprite.color.set_a(alpha);
+            continue;
+        }
+
+        commands.entity(part_entity).despawn();
+        despawned_entities.insert(part_entity);
+        commands
+            .spawn()
+            .insert(ExplosionToSpawn(part_tf.translation));
+        destroyed.send(TargetDestroyed {
+            points: part.points,
+        });
+    }
+
+    let (boss_entity, mut boss, boss_tf) = match boss_query.get_single_mut() {
+        Ok(boss) => boss,
+        Err(_) => return,
+    };
+
+    let parts_left = part_query
+        .iter()
+        .filter(|(entity, ..)| !despawned_entities.contains(entity))
+        .count();
+    if parts_left == 0 {
+        // every hit zone gone, the boss goes down
+        commands.entity(boss_entity).despawn();
+        for i in 0..DEFEAT_EXPLOSIONS {
+            let angle = TAU * i as f32 / DEFEAT_EXPLOSIONS as f32;
+            let offset = Vec3::new(60. * angle.cos(), 30. * angle.sin(), 0.);
+            commands
+                .spawn()
+                .insert(ExplosionToSpawn(boss_tf.translation + offset));
+        }
+        destroyed.send(TargetDestroyed {
+            points: boss.template.points,
+        });
+        commands.remove_resource::<BossHealth>();
+        progress.boss = BossStage::Defeated;
+        return;
+    }
+
+    // next phase once the hit points fall past its threshold
+    if let Some(boss_health) = boss_health {
+        let fraction = boss_health.hp as f32 / boss_health.total as f32;
+        if let Some(phase) = boss.next_phase(fraction) {
+            boss.enter_phase(phase);
+            let pattern = boss.template.phases[phase].pattern.clone();
+            commands
+                .entity(boss_entity)
+                .insert(Emitter::new(pattern, BOSS_GUN_OFFSET));
+        }
+    }
+}
+
+fn boss_cleanup_system(mut commands: Commands) {
+    // the entities go with the other enemies in `game_over_cleanup_system`
+    commands.remove_resource::<BossHealth>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wave::script::WaveScript;
+
+    fn mothership() -> Boss {
+        let template = WaveScript::default().bosses["mothership"].clone();
+        let fire_timer = fire_timer(&template.phases[0]);
+        Boss {
+            template,
+            home: Vec2::ZERO,
+            phase: 0,
+            clock: 0.,
+            fire_timer,
+        }
+    }
+
+    #[test]
+    fn phase_changes_at_its_threshold() {
+        let boss = mothership();
+        let below: Vec<f32> = boss.template.phases.iter().map(|p| p.below).collect();
+        assert_eq!(below, vec![1., 0.6, 0.3]);
+
+        assert_eq!(boss.next_phase(1.), None);
+        assert_eq!(boss.next_phase(0.61), None);
+        assert_eq!(boss.next_phase(0.6), Some(1));
+        assert_eq!(boss.next_phase(0.31), Some(1));
+        assert_eq!(boss.next_phase(0.3), Some(2));
+        assert_eq!(boss.next_phase(0.), Some(2));
+    }
+
+    #[test]
+    fn phase_never_goes_back() {
+        let mut boss = mothership();
+        boss.clock = 5.;
+        boss.enter_phase(2);
+        assert_eq!(boss.phase, 2);
+        assert_eq!(boss.clock, 0.);
+        assert_eq!(boss.next_phase(0.5), None);
+        assert_eq!(boss.next_phase(1.), None);
+    }
+
+    #[test]
+    fn phase_may_skip_one() {
+        // a bomb taking the boss from full health past two thresholds
+        let boss = mothership();
+        assert_eq!(boss.next_phase(0.2), Some(2));
+    }
+}
@@ -1,8 +1,12 @@
+use std::collections::HashSet;
+
 use bevy::{
-    prelude::{Component, Vec2, Vec3},
+    prelude::{Component, Entity, Vec2, Vec3},
     time::Timer,
 };
 
+use crate::config::HeatConfig;
+
 // region: --- Common Components
 
 #[derive(Component)]
@@ -28,6 +32,24 @@ impl From<(f32, f32)> for SpriteSize {
     }
 }
 
+/// Hit points left.
+#[derive(Component)]
+pub struct Health(pub u32);
+
+/// Hit points a laser takes off what it hits.
+#[derive(Component)]
+pub struct Damage(pub u32);
+
+/// Tints a ship for a moment after a hit it survived.
+#[derive(Component)]
+pub struct DamageFlash(pub Timer);
+
+impl Default for DamageFlash {
+    fn default() -> Self {
+        Self(Timer::from_seconds(0.1, false))
+    }
+}
+
 // endregion: --- Common Components
 
 // region: --- Player Components
@@ -38,6 +60,88 @@ pub struct Player;
 #[derive(Component)]
 pub struct FromPlayer;
 
+/// Weapon of the player ship, `level` indexes `player.weapons`.
+#[derive(Component)]
+pub struct Weapon {
+    pub level: usize,
+    /// time left before the next shot
+    pub cooldown: Timer,
+    /// from 0 to 1, see `player.heat`
+    pub heat: f32,
+    /// got full of heat, holding fire until it cools down
+    pub overheated: bool,
+}
+
+impl Weapon {
+    pub fn new(level: usize) -> Self {
+        Self {
+            level,
+            cooldown: Timer::from_seconds(0., false),
+            heat: 0.,
+            overheated: false,
+        }
+    }
+
+    /// One level up, no further than `max_level`.
+    pub fn upgrade(&mut self, max_level: usize) {
+        self.level = (self.level + 1).min(max_level);
+    }
+
+    /// Heat lost over `delta` seconds, firing again once back down to `recover`.
+    pub fn cool(&mut self, heat: &HeatConfig, delta: f32) {
+        self.heat = (self.heat - heat.cooling * delta).max(0.);
+        if self.overheated && self.heat <= heat.recover {
+            self.overheated = false;
+        }
+    }
+
+    /// Heat of one shot, overheating when full.
+    pub fn heat_up(&mut self, heat: &HeatConfig) {
+        self.heat += heat.per_shot;
+        if self.heat >= 1. {
+            self.heat = 1.;
+            self.overheated = true;
+        }
+    }
+}
+
+/// Hit points and shield of the player ship, see `PlayerConfig::armor`.
+#[derive(Component)]
+pub struct Armor {
+    pub hp: u32,
+    pub shield: u32,
+    /// ticks while the shield is below full, `None` when it does not regenerate
+    pub regen: Option<Timer>,
+}
+
+impl Armor {
+    /// Takes a hit on the shield first, then on the hull. Returns true when
+    /// the ship is lost.
+    pub fn hit(&mut self) -> bool {
+        if self.shield > 0 {
+            self.shield -= 1;
+            if let Some(regen) = self.regen.as_mut() {
+                regen.reset();
+            }
+            false
+        } else {
+            self.hp = self.hp.saturating_sub(1);
+            self.hp == 0
+        }
+    }
+}
+
+/// Player projectile going through enemies, with the ones it already hit.
+#[derive(Component, Default)]
+pub struct Piercing(pub HashSet<Entity>);
+
+/// Player projectile turning toward the nearest enemy.
+#[derive(Component)]
+pub struct Homing {
+    /// radians per second
+    pub turn: f32,
+}
+
 // endregion: --- Player Components
 
 // region: --- Enemy Components
@@ -45,9 +149,40 @@ pub struct FromPlayer;
 #[derive(Component)]
 pub struct Enemy;
 
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EnemyKind {
+    Grunt,
+    /// slow and takes several hits
+    Tank,
+    /// fires at the player
+    Sniper,
+    /// fast, never fires, hurts by ramming the player
+    Kamikaze,
+}
+
+impl EnemyKind {
+    pub const ALL: [EnemyKind; 4] = [
+        EnemyKind::Grunt,
+        EnemyKind::Tank,
+        EnemyKind::Sniper,
+        EnemyKind::Kamikaze,
+    ];
+
+    /// Kind from its variant name, as written in wave scripts.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| format!("{kind:?}") == name)
+    }
+}
+
 #[derive(Component)]
 pub struct FromEnemy;
 
+/// Time left before the enemy may fire again.
+#[derive(Component)]
+pub struct FireCooldown(pub Timer);
+
 // endregion: --- Enemy Components
 
 // region: --- Explosion Components
@@ -68,3 +203,73 @@ impl Default for ExplosionTimer {
 }
 
 // endregion: --- Explosion Components
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn weapon_upgrade_stops_at_the_last_level() {
+        let mut weapon = Weapon::new(0);
+        weapon.upgrade(2);
+        assert_eq!(weapon.level, 1);
+        weapon.upgrade(2);
+        assert_eq!(weapon.level, 2);
+        weapon.upgrade(2);
+        assert_eq!(weapon.level, 2);
+    }
+
+    #[test]
+    fn weapon_overheats_and_recovers() {
+        let heat = HeatConfig {
+            per_shot: 0.25,
+            cooling: 0.5,
+            recover: 0.3,
+        };
+        let mut weapon = Weapon::new(0);
+        for _ in 0..3 {
+            weapon.heat_up(&heat);
+        }
+        assert_eq!(weapon.heat, 0.75);
+        assert!(!weapon.overheated);
+
+        weapon.heat_up(&heat);
+        assert_eq!(weapon.heat, 1.);
+        assert!(weapon.overheated);
+
+        // still holding fire above `recover`
+        weapon.cool(&heat, 1.);
+        assert_eq!(weapon.heat, 0.5);
+        assert!(weapon.overheated);
+
+        weapon.cool(&heat, 0.5);
+        assert_eq!(weapon.heat, 0.25);
+        assert!(!weapon.overheated);
+
+        weapon.cool(&heat, 10.);
+        assert_eq!(weapon.heat, 0.);
+    }
+
+    #[test]
+    fn armor_takes_hits_on_the_shield_then_the_hull() {
+        let mut regen = Timer::from_seconds(3., true);
+        regen.tick(Duration::from_secs(2));
+        let mut armor = Armor {
+            hp: 2,
+            shield: 1,
+            regen: Some(regen),
+        };
+
+        assert!(!armor.hit());
+        assert_eq!((armor.hp, armor.shield), (2, 0));
+        // a hit on the shield starts its regeneration over
+        assert_eq!(armor.regen.as_ref().unwrap().elapsed_secs(), 0.);
+
+        assert!(!armor.hit());
+        assert_eq!((armor.hp, armor.shield), (1, 0));
+        assert!(armor.hit());
+        assert_eq!(armor.hp, 0);
+    }
+}
@@ -0,0 +1,932 @@
+use std::{
+    env, fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::reflect::TypeUuid;
+use serde::Deserialize;
+
+use crate::{
+    components::EnemyKind,
+    pattern::{Aim, BulletPattern},
+};
+
+/// Config file looked up in the asset folder when no `--config` is given.
+pub const CONFIG_FILE: &str = "game.config.ron";
+
+// region: --- Config Types
+
+/// Tuning values for the whole game, read from `game.config.ron`.
+///
+/// Every field is optional in the file, missing ones keep their default.
+#[derive(Clone, Debug, Deserialize, TypeUuid)]
+#[uuid = "6f0f2a8e-3c2d-4b7e-9a51-2d8c7e4b1f03"]
+#[serde(default)]
+pub struct GameConfig {
+    /// pixels per second for a velocity of 1
+    pub base_speed: f32,
+    pub sprite_scale: f32,
+    pub sprites: SpriteConfig,
+    pub player: PlayerConfig,
+    pub enemy: EnemyConfig,
+    pub power_ups: PowerUpConfig,
+}
+
+/// Asset paths, relative to the asset folder.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct SpriteConfig {
+    pub player: String,
+    pub enemy_laser: String,
+    pub boss: String,
+    /// 4x4 grid of 64x64 frames
+    pub explosion_sheet: String,
+    pub explosion_len: usize,
+    pub font: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct PlayerConfig {
+    pub size: (f32, f32),
+    pub respawn_delay: f64,
+    pub starting_lives: u32,
+    /// kill counts at which an extra life is awarded, ascending
+    pub extra_life_kills: Vec<u32>,
+    /// seconds after each spawn during which the player cannot be hit
+    pub invulnerable_duration: f64,
+    /// seconds the player cannot be hit after being rammed by an enemy
+    pub ram_invulnerable_duration: f64,
+    /// weapon levels, from the starting one up
+    pub weapons: Vec<WeaponConfig>,
+    /// heat meter limiting sustained fire, `None` fires freely
+    pub heat: Option<HeatConfig>,
+    pub difficulty: Difficulty,
+    /// hit points and shield of each ship, in `Difficulty::Armored`
+    pub armor: ArmorConfig,
+    pub bomb: BombConfig,
+}
+
+/// Smart bomb, clearing the screen of enemy lasers and damaging every enemy.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct BombConfig {
+    /// bombs at the start of a game
+    pub stock: u32,
+    /// most bombs held at once
+    pub max: u32,
+    /// hit points taken off every enemy
+    pub damage: u32,
+    /// seconds the ship cannot be hit after a bomb
+    pub invulnerable_duration: f64,
+}
+
+/// How many hits a ship takes before it is lost.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum Difficulty {
+    /// lost on the first hit
+    Classic,
+    /// hit points and a shield, from `player.armor`
+    Armored,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ArmorConfig {
+    pub hp: u32,
+    /// hits the full shield absorbs
+    pub shield: u32,
+    /// shield a new ship starts with
+    pub shield_start: u32,
+    /// seconds for the shield to regain one hit, 0 never regenerates
+    pub shield_regen: f32,
+}
+
+/// Heat meter filling up as the player fires, from 0 to 1.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct HeatConfig {
+    /// heat added by each shot
+    pub per_shot: f32,
+    /// heat lost per second
+    pub cooling: f32,
+    /// once overheated, firing resumes when the heat is back down to this
+    pub recover: f32,
+}
+
+/// One player weapon level.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct WeaponConfig {
+    pub name: String,
+    /// projectile sprite
+    pub sprite: String,
+    /// projectile size in pixels, before scaling
+    pub size: (f32, f32),
+    pub damage: u32,
+    /// as a `Velocity` length
+    pub speed: f32,
+    /// seconds between two shots
+    pub cooldown: f32,
+    /// one projectile each per shot
+    pub shots: Vec<ShotConfig>,
+    /// goes through enemies instead of stopping at the first one hit
+    pub piercing: bool,
+    /// radians per second the projectile turns toward the nearest enemy, 0 flies straight
+    pub homing: f32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ShotConfig {
+    /// from the ship center, in pixels
+    pub offset: (f32, f32),
+    /// radians from straight up, positive to the left
+    #[serde(default)]
+    pub angle: f32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct EnemyConfig {
+    pub laser_size: (f32, f32),
+    /// enemies on screen at once
+    pub max: u32,
+    /// average volleys per second, for each enemy
+    pub fire_rate: f64,
+    /// fraction the time between two volleys varies by, either way
+    pub fire_jitter: f64,
+    /// enemy lasers on screen at once, enemies hold their fire above it
+    pub max_lasers: usize,
+    /// pixels left or right of the player an enemy still fires from
+    pub fire_range: f32,
+    /// hit points an enemy loses when it rams the player
+    pub ram_damage: u32,
+    pub formation: FormationConfig,
+    pub dive: DiveConfig,
+    pub grunt: EnemyKindConfig,
+    pub tank: EnemyKindConfig,
+    pub sniper: EnemyKindConfig,
+    pub kamikaze: EnemyKindConfig,
+}
+
+/// Look and stats of one `EnemyKind`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct EnemyKindConfig {
+    pub sprite: String,
+    /// tint multiplied with the sprite
+    pub color: (f32, f32, f32),
+    /// sprite size in pixels, before scaling
+    pub size: (f32, f32),
+    /// multiplier on `sprite_scale`
+    pub scale: f32,
+    pub hp: u32,
+    /// points for a kill while orbiting, before the chain multiplier
+    pub points: u32,
+    /// multiplier on the formation speed
+    pub speed: f32,
+    /// lasers fired each time the kind fires, `None` never fires
+    pub pattern: Option<BulletPattern>,
+    /// leaves its formation for dive attacks
+    pub dives: bool,
+    /// chances of dropping each power-up when destroyed
+    pub drops: DropConfig,
+}
+
+/// Chance, from 0 to 1, of each power-up dropping. At most one drops, so
+/// they add up to 1 at most.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DropConfig {
+    pub weapon: f64,
+    pub shield: f64,
+    pub extra_life: f64,
+    pub bomb: f64,
+    pub score: f64,
+}
+
+impl DropConfig {
+    pub fn total(&self) -> f64 {
+        self.weapon + self.shield + self.extra_life + self.bomb + self.score
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct PowerUpConfig {
+    /// pixels
+    pub size: (f32, f32),
+    /// downward drift, as a `Velocity` length
+    pub speed: f32,
+    /// points for a score power-up, not affected by the chain multiplier
+    pub score_bonus: u32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct FormationConfig {
+    /// range the horizontal radius of the ellipse is picked from
+    pub x_radius: (f32, f32),
+    pub y_radius: f32,
+}
+
+/// Attack runs out of the formation orbit, down toward the player.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct DiveConfig {
+    /// average seconds between two dives, 0 never dives
+    pub interval: f64,
+    /// enemies diving at once
+    pub max: usize,
+    /// multiplier on the formation speed
+    pub speed: f32,
+    /// radians per second a diving enemy turns toward the player
+    pub turn: f32,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            base_speed: 500.,
+            sprite_scale: 0.5,
+            sprites: SpriteConfig::default(),
+            player: PlayerConfig::default(),
+            enemy: EnemyConfig::default(),
+            power_ups: PowerUpConfig::default(),
+        }
+    }
+}
+
+impl Default for SpriteConfig {
+    fn default() -> Self {
+        Self {
+            player: "player_a_01.png".to_string(),
+            enemy_laser: "laser_b_01.png".to_string(),
+            boss: "enemy_a_01.png".to_string(),
+            explosion_sheet: "explo_a_sheet.png".to_string(),
+            explosion_len: 16,
+            font: "fonts/DejaVuSans-Bold.ttf".to_string(),
+        }
+    }
+}
+
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        Self {
+            size: (144., 75.),
+            respawn_delay: 2.,
+            starting_lives: 3,
+            extra_life_kills: vec![10, 30, 60],
+            invulnerable_duration: 2.,
+            ram_invulnerable_duration: 0.5,
+            weapons: vec![
+                WeaponConfig::default(),
+                WeaponConfig {
+                    name: "triple spread".to_string(),
+                    shots: vec![
+                        shot(-20., 10., 0.2),
+                        shot(0., 20., 0.),
+                        shot(20., 10., -0.2),
+                    ],
+                    ..Default::default()
+                },
+                WeaponConfig {
+                    name: "rapid fire".to_string(),
+                    cooldown: 0.1,
+                    ..Default::default()
+                },
+                WeaponConfig {
+                    name: "piercing laser".to_string(),
+                    damage: 2,
+                    speed: 1.5,
+                    cooldown: 0.2,
+                    piercing: true,
+                    ..Default::default()
+                },
+                WeaponConfig {
+                    name: "homing missiles".to_string(),
+                    damage: 2,
+                    speed: 0.8,
+                    cooldown: 0.3,
+                    shots: vec![shot(-31., 15., 0.4), shot(31., 15., -0.4)],
+                    homing: 4.,
+                    ..Default::default()
+                },
+            ],
+            heat: Some(HeatConfig::default()),
+            difficulty: Difficulty::Classic,
+            armor: ArmorConfig::default(),
+            bomb: BombConfig::default(),
+        }
+    }
+}
+
+impl PlayerConfig {
+    /// Armor of each new ship, one hit point and no shield in classic mode.
+    pub fn armor(&self) -> ArmorConfig {
+        match self.difficulty {
+            Difficulty::Classic => ArmorConfig {
+                hp: 1,
+                shield: 0,
+                shield_start: 0,
+                shield_regen: 0.,
+            },
+            Difficulty::Armored => self.armor.clone(),
+        }
+    }
+}
+
+impl Default for BombConfig {
+    fn default() -> Self {
+        Self {
+            stock: 2,
+            max: 5,
+            damage: 3,
+            invulnerable_duration: 1.,
+        }
+    }
+}
+
+impl Default for ArmorConfig {
+    fn default() -> Self {
+        Self {
+            hp: 3,
+            shield: 2,
+            shield_start: 1,
+            shield_regen: 8.,
+        }
+    }
+}
+
+impl Default for HeatConfig {
+    fn default() -> Self {
+        Self {
+            per_shot: 0.08,
+            cooling: 0.35,
+            recover: 0.3,
+        }
+    }
+}
+
+impl Default for WeaponConfig {
+    /// The twin shot, the starting weapon.
+    fn default() -> Self {
+        Self {
+            name: "twin shot".to_string(),
+            sprite: "laser_a_01.png".to_string(),
+            size: (9., 54.),
+            damage: 1,
+            speed: 1.,
+            cooldown: 0.25,
+            shots: vec![shot(-31., 15., 0.), shot(31., 15., 0.)],
+            piercing: false,
+            homing: 0.,
+        }
+    }
+}
+
+fn shot(x: f32, y: f32, angle: f32) -> ShotConfig {
+    ShotConfig {
+        offset: (x, y),
+        angle,
+    }
+}
+
+impl Default for EnemyConfig {
+    fn default() -> Self {
+        Self {
+            laser_size: (17., 55.),
+            max: 4,
+            fire_rate: 1.,
+            fire_jitter: 0.5,
+            max_lasers: 24,
+            fire_range: 250.,
+            ram_damage: 2,
+            formation: FormationConfig::default(),
+            dive: DiveConfig::default(),
+            grunt: EnemyKindConfig::default(),
+            tank: EnemyKindConfig {
+                color: (0.6, 0.8, 1.),
+                scale: 1.3,
+                hp: 4,
+                points: 300,
+                speed: 0.7,
+                // holds the formation
+                dives: false,
+                pattern: Some(BulletPattern {
+                    ways: 3,
+                    spread: 0.6,
+                    ..Default::default()
+                }),
+                drops: DropConfig {
+                    weapon: 0.15,
+                    shield: 0.05,
+                    extra_life: 0.02,
+                    bomb: 0.05,
+                    score: 0.1,
+                },
+                ..Default::default()
+            },
+            sniper: EnemyKindConfig {
+                color: (1., 1., 0.5),
+                scale: 0.9,
+                hp: 2,
+                points: 200,
+                speed: 0.9,
+                pattern: Some(BulletPattern {
+                    aim: Aim::Player,
+                    burst: 3,
+                    burst_interval: 0.12,
+                    speed: 1.3,
+                    ..Default::default()
+                }),
+                drops: DropConfig {
+                    weapon: 0.1,
+                    shield: 0.04,
+                    extra_life: 0.01,
+                    bomb: 0.03,
+                    score: 0.08,
+                },
+                ..Default::default()
+            },
+            kamikaze: EnemyKindConfig {
+                color: (1., 0.5, 0.5),
+                scale: 0.8,
+                points: 150,
+                speed: 1.6,
+                pattern: None,
+                drops: DropConfig {
+                    bomb: 0.02,
+                    score: 0.1,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl Default for EnemyKindConfig {
+    /// The grunt: one hit, fires straight down.
+    fn default() -> Self {
+        Self {
+            sprite: "enemy_a_01.png".to_string(),
+            color: (1., 1., 1.),
+            size: (144., 75.),
+            scale: 1.,
+            hp: 1,
+            points: 100,
+            speed: 1.,
+            pattern: Some(BulletPattern::default()),
+            dives: true,
+            drops: DropConfig {
+                weapon: 0.05,
+                shield: 0.02,
+                extra_life: 0.005,
+                bomb: 0.01,
+                score: 0.05,
+            },
+        }
+    }
+}
+
+impl Default for PowerUpConfig {
+    fn default() -> Self {
+        Self {
+            size: (24., 24.),
+            speed: 0.3,
+            score_bonus: 500,
+        }
+    }
+}
+
+impl EnemyConfig {
+    pub fn kind(&self, kind: EnemyKind) -> &EnemyKindConfig {
+        match kind {
+            EnemyKind::Grunt => &self.grunt,
+            EnemyKind::Tank => &self.tank,
+            EnemyKind::Sniper => &self.sniper,
+            EnemyKind::Kamikaze => &self.kamikaze,
+        }
+    }
+}
+
+impl Default for DiveConfig {
+    fn default() -> Self {
+        Self {
+            interval: 3.,
+            max: 2,
+            speed: 1.5,
+            turn: 3.,
+        }
+    }
+}
+
+impl Default for FormationConfig {
+    fn default() -> Self {
+        Self {
+            x_radius: (80., 150.),
+            y_radius: 100.,
+        }
+    }
+}
+
+// endregion: --- Config Types
+
+// region: --- Loading
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, String),
+    /// one message per invalid field
+    Invalid(PathBuf, Vec<String>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, err) => write!(f, "cannot read {path:?}: {err}"),
+            ConfigError::Parse(path, err) => write!(f, "cannot parse {path:?}: {err}"),
+            ConfigError::Invalid(path, errors) => {
+                write!(f, "invalid config {path:?}:")?;
+                for err in errors {
+                    write!(f, "\n  - {err}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl ConfigError {
+    pub(crate) fn with_path(self, path: &Path) -> Self {
+        match self {
+            ConfigError::Io(_, err) => ConfigError::Io(path.to_path_buf(), err),
+            ConfigError::Parse(_, msg) => ConfigError::Parse(path.to_path_buf(), msg),
+            ConfigError::Invalid(_, errors) => ConfigError::Invalid(path.to_path_buf(), errors),
+        }
+    }
+}
+
+impl GameConfig {
+    /// Reads and validates a config file, a missing file gives the defaults.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::read(path)
+    }
+
+    /// Like `load`, but a missing file is an error.
+    pub fn read(path: &Path) -> Result<Self, ConfigError> {
+        let content =
+            fs::read_to_string(path).map_err(|err| ConfigError::Io(path.to_path_buf(), err))?;
+        Self::from_ron(&content).map_err(|err| err.with_path(path))
+    }
+
+    pub fn from_ron(content: &str) -> Result<Self, ConfigError> {
+        let config: GameConfig = ron::from_str(content)
+            .map_err(|err| ConfigError::Parse(PathBuf::new(), err.to_string()))?;
+        config
+            .validate()
+            .map_err(|errors| ConfigError::Invalid(PathBuf::new(), errors))?;
+        Ok(config)
+    }
+
+    /// `--config <path>` from the command line.
+    pub fn path_from_args(args: impl IntoIterator<Item = String>) -> Option<PathBuf> {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--config" {
+                return args.next().map(PathBuf::from);
+            }
+        }
+        None
+    }
+
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        let mut check = |ok: bool, msg: &str| {
+            if !ok {
+                errors.push(msg.to_string());
+            }
+        };
+        let positive = |size: (f32, f32)| size.0 > 0. && size.1 > 0.;
+        let mut pattern_errors = Vec::new();
+
+        check(self.base_speed > 0., "base_speed must be greater than 0");
+        check(
+            self.sprite_scale > 0.,
+            "sprite_scale must be greater than 0",
+        );
+
+        let sprites = &self.sprites;
+        for (name, path) in [
+            ("sprites.player", &sprites.player),
+            ("sprites.enemy_laser", &sprites.enemy_laser),
+            ("sprites.boss", &sprites.boss),
+            ("sprites.explosion_sheet", &sprites.explosion_sheet),
+            ("sprites.font", &sprites.font),
+        ] {
+            check(!path.is_empty(), &format!("{name} must not be empty"));
+        }
+        check(
+            (1..=16).contains(&sprites.explosion_len),
+            "sprites.explosion_len must be between 1 and 16 (4x4 sheet)",
+        );
+
+        let player = &self.player;
+        check(positive(player.size), "player.size must be positive");
+        check(
+            !player.weapons.is_empty(),
+            "player.weapons must not be empty",
+        );
+        for (i, weapon) in player.weapons.iter().enumerate() {
+            let name = format!("player.weapons[{i}]");
+            check(
+                !weapon.sprite.is_empty(),
+                &format!("{name}.sprite must not be empty"),
+            );
+            check(
+                positive(weapon.size),
+                &format!("{name}.size must be positive"),
+            );
+            check(
+                weapon.damage >= 1,
+                &format!("{name}.damage must be at least 1"),
+            );
+            check(
+                weapon.speed > 0.,
+                &format!("{name}.speed must be greater than 0"),
+            );
+            check(
+                weapon.cooldown >= 0.,
+                &format!("{name}.cooldown must not be negative"),
+            );
+            check(
+                !weapon.shots.is_empty(),
+                &format!("{name}.shots must not be empty"),
+            );
+            check(
+                weapon.homing >= 0.,
+                &format!("{name}.homing must not be negative"),
+            );
+        }
+        let armor = &player.armor;
+        check(armor.hp >= 1, "player.armor.hp must be at least 1");
+        check(
+            armor.shield_start <= armor.shield,
+            "player.armor.shield_start must not be above player.armor.shield",
+        );
+        check(
+            armor.shield_regen >= 0.,
+            "player.armor.shield_regen must not be negative",
+        );
+        let bomb = &player.bomb;
+        check(
+            bomb.stock <= bomb.max,
+            "player.bomb.stock must not be above player.bomb.max",
+        );
+        check(bomb.damage >= 1, "player.bomb.damage must be at least 1");
+        check(
+            bomb.invulnerable_duration >= 0.,
+            "player.bomb.invulnerable_duration must not be negative",
+        );
+        if let Some(heat) = &player.heat {
+            check(
+                0. < heat.per_shot && heat.per_shot <= 1.,
+                "player.heat.per_shot must be in (0, 1]",
+            );
+            check(
+                heat.cooling > 0.,
+                "player.heat.cooling must be greater than 0",
+            );
+            check(
+                (0. ..1.).contains(&heat.recover),
+                "player.heat.recover must be in [0, 1)",
+            );
+        }
+        check(
+            player.respawn_delay >= 0.,
+            "player.respawn_delay must not be negative",
+        );
+        check(
+            player.starting_lives >= 1,
+            "player.starting_lives must be at least 1",
+        );
+        check(
+            player
+                .extra_life_kills
+                .windows(2)
+                .all(|pair| pair[0] < pair[1]),
+            "player.extra_life_kills must be strictly ascending",
+        );
+        check(
+            player.invulnerable_duration >= 0.,
+            "player.invulnerable_duration must not be negative",
+        );
+        check(
+            player.ram_invulnerable_duration >= 0.,
+            "player.ram_invulnerable_duration must not be negative",
+        );
+
+        let enemy = &self.enemy;
+        for kind in EnemyKind::ALL {
+            let name = format!("enemy.{}", format!("{kind:?}").to_lowercase());
+            let kind = enemy.kind(kind);
+            check(
+                !kind.sprite.is_empty(),
+                &format!("{name}.sprite must not be empty"),
+            );
+            check(
+                positive(kind.size),
+                &format!("{name}.size must be positive"),
+            );
+            check(
+                kind.scale > 0.,
+                &format!("{name}.scale must be greater than 0"),
+            );
+            check(kind.hp >= 1, &format!("{name}.hp must be at least 1"));
+            check(
+                kind.speed > 0.,
+                &format!("{name}.speed must be greater than 0"),
+            );
+            if let Some(pattern) = &kind.pattern {
+                pattern_errors.extend(pattern.validate(&format!("{name}.pattern")));
+            }
+            let drops = &kind.drops;
+            check(
+                [
+                    drops.weapon,
+                    drops.shield,
+                    drops.extra_life,
+                    drops.bomb,
+                    drops.score,
+                ]
+                .iter()
+                .all(|chance| *chance >= 0.)
+                    && drops.total() <= 1.,
+                &format!("{name}.drops must not be negative and add up to 1 at most"),
+            );
+        }
+        check(
+            positive(enemy.laser_size),
+            "enemy.laser_size must be positive",
+        );
+        check(enemy.max >= 1, "enemy.max must be at least 1");
+        check(
+            enemy.fire_rate >= 0.,
+            "enemy.fire_rate must not be negative",
+        );
+        check(
+            (0. ..1.).contains(&enemy.fire_jitter),
+            "enemy.fire_jitter must be in [0, 1)",
+        );
+        check(
+            enemy.fire_range > 0.,
+            "enemy.fire_range must be greater than 0",
+        );
+        check(enemy.ram_damage >= 1, "enemy.ram_damage must be at least 1");
+        let formation = &enemy.formation;
+        check(
+            0. < formation.x_radius.0 && formation.x_radius.0 < formation.x_radius.1,
+            "enemy.formation.x_radius must be a (min, max) range with 0 < min < max",
+        );
+        check(
+            formation.y_radius > 0.,
+            "enemy.formation.y_radius must be greater than 0",
+        );
+        let dive = &enemy.dive;
+        check(
+            dive.interval >= 0.,
+            "enemy.dive.interval must not be negative",
+        );
+        check(dive.speed > 0., "enemy.dive.speed must be greater than 0");
+        check(dive.turn > 0., "enemy.dive.turn must be greater than 0");
+
+        let power_ups = &self.power_ups;
+        check(positive(power_ups.size), "power_ups.size must be positive");
+        check(
+            power_ups.speed > 0.,
+            "power_ups.speed must be greater than 0",
+        );
+
+        errors.extend(pattern_errors);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Same lookup as bevy's `FileAssetIo`: next to the manifest when run
+/// through cargo, next to the executable otherwise.
+pub fn asset_dir() -> PathBuf {
+    let base = env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .or_else(|| {
+            env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        })
+        .unwrap_or_default();
+    base.join("assets")
+}
+
+// endregion: --- Loading
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid(content: &str) -> Vec<String> {
+        match GameConfig::from_ron(content) {
+            Err(ConfigError::Invalid(_, errors)) => errors,
+            other => panic!("expected validation errors, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        assert_eq!(GameConfig::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn shipped_config_is_valid() {
+        GameConfig::read(&asset_dir().join(CONFIG_FILE)).unwrap();
+    }
+
+    #[test]
+    fn missing_file_loads_the_defaults_but_does_not_read() {
+        let path = asset_dir().join("no-such.config.ron");
+        let config = GameConfig::load(&path).unwrap();
+        assert_eq!(config.base_speed, GameConfig::default().base_speed);
+        assert!(matches!(GameConfig::read(&path), Err(ConfigError::Io(..))));
+    }
+
+    #[test]
+    fn config_path_only_from_args() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            GameConfig::path_from_args(args(&["game", "--headless"])),
+            None
+        );
+        assert_eq!(
+            GameConfig::path_from_args(args(&["game", "--config", "mine.ron"])),
+            Some(PathBuf::from("mine.ron"))
+        );
+    }
+
+    #[test]
+    fn classic_is_the_default_difficulty() {
+        assert_eq!(PlayerConfig::default().difficulty, Difficulty::Classic);
+        let shipped = GameConfig::load(&asset_dir().join(CONFIG_FILE)).unwrap();
+        assert_eq!(shipped.player.difficulty, Difficulty::Classic);
+    }
+
+    #[test]
+    fn missing_fields_keep_their_default() {
+        let config = GameConfig::from_ron("(base_speed: 300.)").unwrap();
+        assert_eq!(config.base_speed, 300.);
+        assert_eq!(config.sprite_scale, GameConfig::default().sprite_scale);
+    }
+
+    #[test]
+    fn every_invalid_field_is_reported() {
+        let errors = invalid("(base_speed: 0., sprite_scale: -1., player: (starting_lives: 0))");
+        assert_eq!(
+            errors,
+            vec![
+                "base_speed must be greater than 0",
+                "sprite_scale must be greater than 0",
+                "player.starting_lives must be at least 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn drops_must_add_up_to_one_at_most() {
+        let mut config = GameConfig::default();
+        config.enemy.grunt.drops.weapon = 0.6;
+        config.enemy.grunt.drops.shield = 0.6;
+        assert_eq!(
+            config.validate(),
+            Err(vec![
+                "enemy.grunt.drops must not be negative and add up to 1 at most".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn syntax_errors_are_parse_errors() {
+        assert!(matches!(
+            GameConfig::from_ron("(base_speed: "),
+            Err(ConfigError::Parse(..))
+        ));
+    }
+}
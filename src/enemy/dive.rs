@@ -0,0 +1,289 @@
+use std::{
+    f32::consts::{FRAC_PI_2, PI, TAU},
+    time::Duration,
+};
+
+use bevy::{math::Vec3Swizzles, prelude::*};
+use rand::Rng;
+
+use crate::{
+    components::{EnemyKind, FireCooldown, Player},
+    config::GameConfig,
+    rng::GameRng,
+    WinSize,
+};
+
+use super::formation::Formation;
+
+/// pixels past the edge of the window a diving enemy leaves and comes back in at
+const DIVE_MARGIN: f32 = 80.;
+/// seconds into the dive before the first volley
+const DIVE_FIRST_VOLLEY: f32 = 0.4;
+
+/// Attack run out of the formation orbit, taking over from `Formation` until
+/// the enemy comes back in from the top.
+#[derive(Component)]
+pub struct Dive {
+    /// radians, 0 pointing right
+    heading: f32,
+    /// player position when the dive started, the curve bends toward it
+    target: Vec2,
+}
+
+/// Sends an orbiting enemy on a dive every `enemy.dive.interval` seconds on average.
+#[allow(clippy::too_many_arguments)]
+pub fn dive_start_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
+    mut timer: Local<Timer>,
+    player_query: Query<&Transform, With<Player>>,
+    dive_query: Query<(), With<Dive>>,
+    mut enemy_query: Query<
+        (
+            Entity,
+            &EnemyKind,
+            &mut Formation,
+            Option<&mut FireCooldown>,
+        ),
+        Without<Dive>,
+    >,
+) {
+    if !timer.tick(time.delta()).finished() {
+        return;
+    }
+    let dive = &config.enemy.dive;
+    if dive.interval <= 0. {
+        return;
+    }
+    let interval = dive.interval * rng.gen_range(0.5..=1.5);
+    timer.set_duration(Duration::from_secs_f64(interval));
+    timer.reset();
+
+    // no one to dive at, or enough of them diving already
+    let player_tf = match player_query.get_single() {
+        Ok(player_tf) => player_tf,
+        Err(_) => return,
+    };
+    if dive_query.iter().count() >= dive.max {
+        return;
+    }
+
+    let candidates: Vec<Entity> = enemy_query
+        .iter()
+        .filter(|(_, kind, formation, _)| formation.orbiting && config.enemy.kind(**kind).dives)
+        .map(|(entity, ..)| entity)
+        .collect();
+    if candidates.is_empty() {
+        return;
+    }
+    let entity = candidates[rng.gen_range(0..candidates.len())];
+
+    if let Ok((_, _, mut formation, cooldown)) = enemy_query.get_mut(entity) {
+        // off the ellipse, worth the same bonus as while flying in
+        formation.orbiting = false;
+        // fires on the way down
+        if let Some(mut cooldown) = cooldown {
+            *cooldown = FireCooldown(Timer::from_seconds(DIVE_FIRST_VOLLEY, false));
+        }
+        // pulls up first, then loops down toward the player
+        commands.entity(entity).insert(Dive {
+            heading: FRAC_PI_2,
+            target: player_tf.translation.xy(),
+        });
+    }
+}
+
+pub fn dive_movement_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    win_size: Res<WinSize>,
+    mut query: Query<(Entity, &mut Dive, &mut Transform, &mut Formation)>,
+) {
+    let delta = time.delta_seconds();
+    let dive_config = &config.enemy.dive;
+
+    for (entity, mut dive, mut tf, mut formation) in query.iter_mut() {
+        // its place on the ellipse keeps going round with the others
+        formation.angle += formation.angle_step(delta);
+
+        let position = tf.translation.xy();
+
+        // toward the player until level with it, then straight down and out
+        let wanted = if position.y > dive.target.y {
+            let to_target = dive.target - position;
+            to_target.y.atan2(to_target.x)
+        } else {
+            -FRAC_PI_2
+        };
+        // the shortest way round, no faster than the turn rate
+        let turn = (wanted - dive.heading + PI).rem_euclid(TAU) - PI;
+        let max_turn = dive_config.turn * delta;
+        dive.heading += turn.clamp(-max_turn, max_turn);
+
+        let step = delta * formation.speed * dive_config.speed;
+        tf.translation.x += dive.heading.cos() * step;
+        tf.translation.y += dive.heading.sin() * step;
+
+        // out the bottom, back in from the top and flying to its place on the ellipse
+        if tf.translation.y < -win_size.h / 2. - DIVE_MARGIN {
+            let x_span = win_size.w / 2.;
+            tf.translation.x = tf.translation.x.clamp(-x_span, x_span);
+            tf.translation.y = win_size.h / 2. + DIVE_MARGIN;
+            commands.entity(entity).remove::<Dive>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        components::{Damage, Enemy, FromEnemy, FromPlayer, Laser, SpriteSize},
+        headless::{HeadlessConfig, HeadlessPlugin},
+        state::GameState,
+        GamePlugin,
+    };
+    use std::time::Instant;
+
+    const WIN_SIZE: WinSize = WinSize { w: 600., h: 800. };
+
+    /// World stepping the dives by `delta` seconds.
+    fn world(delta: f32) -> World {
+        let mut time = Time::default();
+        let start = Instant::now();
+        time.update_with_instant(start);
+        time.update_with_instant(start + Duration::from_secs_f32(delta));
+
+        let mut world = World::new();
+        world.insert_resource(time);
+        world.insert_resource(GameConfig::default());
+        world.insert_resource(WIN_SIZE);
+        world
+    }
+
+    fn spawn_diver(world: &mut World, position: Vec2, heading: f32, target: Vec2) -> Entity {
+        world
+            .spawn()
+            .insert(Dive { heading, target })
+            .insert(Transform::from_translation(position.extend(0.)))
+            .insert(Formation {
+                start: (-400., 0.),
+                radius: (100., 50.),
+                pivot: (0., 200.),
+                speed: 100.,
+                angle: 0.,
+                orbiting: false,
+            })
+            .id()
+    }
+
+    fn step(world: &mut World) {
+        SystemStage::single_threaded()
+            .with_system(dive_movement_system)
+            .run(world);
+    }
+
+    #[test]
+    fn dive_turns_toward_the_target_no_faster_than_the_turn_rate() {
+        let mut world = world(0.1);
+        let turn = world.resource::<GameConfig>().enemy.dive.turn;
+        // pulling up, the player below and to the right
+        let diver = spawn_diver(&mut world, Vec2::ZERO, FRAC_PI_2, Vec2::new(200., -200.));
+
+        step(&mut world);
+        let heading = world.get::<Dive>(diver).unwrap().heading;
+        assert!((heading - (FRAC_PI_2 - turn * 0.1)).abs() < 1e-5);
+        let position = world.get::<Transform>(diver).unwrap().translation;
+        assert!(position.y > 0.);
+    }
+
+    #[test]
+    fn dive_goes_straight_down_once_past_the_target() {
+        let mut world = world(0.1);
+        let diver = spawn_diver(
+            &mut world,
+            Vec2::new(50., -300.),
+            -FRAC_PI_2,
+            Vec2::new(200., -200.),
+        );
+
+        step(&mut world);
+        assert_eq!(world.get::<Dive>(diver).unwrap().heading, -FRAC_PI_2);
+        let position = world.get::<Transform>(diver).unwrap().translation;
+        assert!((position.x - 50.).abs() < 1e-3);
+        assert!(position.y < -300.);
+    }
+
+    #[test]
+    fn dive_out_the_bottom_comes_back_in_from_the_top() {
+        let mut world = world(0.1);
+        let bottom = -WIN_SIZE.h / 2. - DIVE_MARGIN;
+        let diver = spawn_diver(
+            &mut world,
+            Vec2::new(WIN_SIZE.w, bottom + 1.),
+            -FRAC_PI_2,
+            Vec2::ZERO,
+        );
+
+        step(&mut world);
+        assert!(world.get::<Dive>(diver).is_none());
+        let position = world.get::<Transform>(diver).unwrap().translation;
+        assert_eq!(position.x, WIN_SIZE.w / 2.);
+        assert_eq!(position.y, WIN_SIZE.h / 2. + DIVE_MARGIN);
+        assert!(!world.get::<Formation>(diver).unwrap().orbiting);
+    }
+
+    #[test]
+    fn shot_on_the_frame_its_dive_starts() {
+        // a dive every frame, at whichever enemy is still in orbit
+        let mut config = GameConfig::default();
+        config.enemy.dive.interval = 0.001;
+        config.enemy.dive.max = usize::MAX;
+        config.player.starting_lives = 100;
+        let mut app = App::new();
+        app.insert_resource(GameRng::with_seed(1))
+            .insert_resource(config)
+            .add_plugin(HeadlessPlugin(HeadlessConfig::default()))
+            .add_plugin(GamePlugin);
+
+        let mut shots = 0;
+        for _ in 0..60 * 30 {
+            app.update();
+            let world = &mut app.world;
+
+            // no enemy fire, the dives need a player to go for
+            let enemy_lasers: Vec<Entity> = world
+                .query_filtered::<Entity, With<FromEnemy>>()
+                .iter(world)
+                .collect();
+            for laser in enemy_lasers {
+                world.despawn(laser);
+            }
+
+            // a laser on every enemy in orbit, the one picked next is hit on that frame
+            let targets: Vec<Vec3> = world
+                .query_filtered::<(&Transform, &Formation), (With<Enemy>, Without<Dive>)>()
+                .iter(world)
+                .filter(|(_, formation)| formation.orbiting)
+                .map(|(tf, _)| tf.translation)
+                .collect();
+            shots += targets.len();
+            for translation in targets {
+                world
+                    .spawn()
+                    .insert(Laser)
+                    .insert(FromPlayer)
+                    .insert(Damage(u32::MAX))
+                    .insert(SpriteSize::from((10., 10.)))
+                    .insert(Transform::from_translation(translation));
+            }
+        }
+
+        assert!(shots > 0);
+        let state = app.world.resource::<State<GameState>>();
+        assert_eq!(state.current(), &GameState::Playing);
+    }
+}
@@ -1,6 +1,23 @@
-use crate::{WinSize, BASE_SPEED, FORMATION_MEMBERS_MAX};
-use bevy::{prelude::Component, time::Time};
-use rand::{thread_rng, Rng};
+use crate::{
+    config::GameConfig,
+    wave::script::{FormationTemplate, Side},
+    WinSize,
+};
+use bevy::prelude::Component;
+use rand::Rng;
+use serde::Deserialize;
+use std::f32::consts::PI;
+
+/// Outline of the ellipse a formation orbits on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum FormationShape {
+    /// width picked from `enemy.formation.x_radius`
+    Ellipse,
+    /// as wide as high
+    Circle,
+    /// flat and wide, independent of `x_radius`
+    Wide,
+}
 
 #[derive(Component, Clone)]
 pub struct Formation {
@@ -9,73 +26,69 @@ pub struct Formation {
     pub pivot: (f32, f32),
     pub speed: f32,
     pub angle: f32,
+    /// false while still flying in to the ellipse
+    pub orbiting: bool,
 }
 
-#[derive(Default)]
-pub struct FormationMaker {
-    current_template: Option<Formation>,
-    current_members: u32,
-}
-
-impl FormationMaker {
-   
-    pub fn make(&mut self, win_size: &WinSize) -> Formation {
-        match (
-            &self.current_template,
-            self.current_members >= FORMATION_MEMBERS_MAX,
-        ) {
-            // if has current template ans still within max member
-            (Some(templ), fasle) => {
-                self.current_members += 1;
-                templ.clone()
-            }
-            // if first formation or previous formation is null (need to create a new one)
-            (None, _) | (_, true) => {
-                let mut rng = thread_rng();
-
-                // computer the start x/y
-                let w_span = win_size.w / 2. + 100.;
-                let h_span = win_size.h / 2. + 100.;
-                let x = w_span;
-                let y = rng.gen_range(-h_span..h_span) as f32;
-                let start = (x, y);
-
-                // computer the pivot x/y
-                let w_span = win_size.w / 4.;
-                let h_span = win_size.h / 3. - 50.;
-                let pivot = (rng.gen_range(-w_span..w_span), rng.gen_range(0.0..h_span));
-
-                // computer the radius
-                let radius = (rng.gen_range(80.0..150.), 100.);
+impl Formation {
+    /// New formation for a spawn event, shared by all its members.
+    pub fn new(
+        win_size: &WinSize,
+        config: &GameConfig,
+        template: &FormationTemplate,
+        side: Side,
+        speed_scale: f32,
+        rng: &mut impl Rng,
+    ) -> Formation {
+        let formation_config = &config.enemy.formation;
 
-                // computer the start angle
-                let angle = (y - pivot.1).atan2(x - pivot.0);
+        // computer the start x/y
+        let w_span = win_size.w / 2. + 100.;
+        let h_span = win_size.h / 2. + 100.;
+        let from_left = match side {
+            Side::Left => true,
+            Side::Right => false,
+            Side::Random => rng.gen_bool(0.5),
+        };
+        let x = if from_left { -w_span } else { w_span };
+        let y = rng.gen_range(-h_span..h_span);
+        let start = (x, y);
 
-                // speed (fixed for now)
-                let speed = BASE_SPEED;
+        // computer the pivot x/y
+        let w_span = win_size.w / 4.;
+        let h_span = win_size.h / 3. - 50.;
+        let pivot = (rng.gen_range(-w_span..w_span), rng.gen_range(0.0..h_span));
 
-                // create Formation
-                let formation = Formation {
-                    start,
-                    pivot,
-                    radius,
-                    angle,
-                    speed,
-                };
+        // computer the radius
+        let (x_radius_min, x_radius_max) = formation_config.x_radius;
+        let y_radius = formation_config.y_radius;
+        let radius = match template.shape {
+            FormationShape::Ellipse => (rng.gen_range(x_radius_min..x_radius_max), y_radius),
+            FormationShape::Circle => (y_radius, y_radius),
+            FormationShape::Wide => (win_size.w / 5., y_radius / 2.),
+        };
 
-                // store as template
-                self.current_template = Some(formation.clone());
+        // computer the start angle
+        let angle = (y - pivot.1).atan2(x - pivot.0);
 
-                // reset member to 1
-                self.current_members = 1;
+        // speed
+        let speed = config.base_speed * template.speed * speed_scale;
 
-                formation
-            }
+        Formation {
+            start,
+            pivot,
+            radius,
+            angle,
+            speed,
+            orbiting: false,
         }
     }
-}
-impl Formation {
-
-
 
+    /// Radians the formation turns on its ellipse in `delta` seconds.
+    pub fn angle_step(&self, delta: f32) -> f32 {
+        // 1 counter clockwise ; -1 clockwise
+        let dir: f32 = if self.start.0 < 0. { 1. } else { -1. };
+        let (x_radius, y_radius) = self.radius;
+        dir * self.speed * delta / (x_radius.min(y_radius) * PI / 2.)
+    }
 }
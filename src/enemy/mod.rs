@@ -1,16 +1,25 @@
-use std::f32::consts::PI;
-mod formation;
+pub mod dive;
+pub mod formation;
 
-use bevy::{ecs::schedule::ShouldRun, prelude::*, time::FixedTimestep};
-use rand::{thread_rng, Rng};
+use bevy::prelude::*;
+use rand::Rng;
 
 use crate::{
-    components::{Enemy, FromEnemy, Laser, Moveable, SpriteSize, Velocity},
-    EnemyCount, GameTextures, WinSize, BASE_SPEED, ENEMY_LASER_SIZE, ENEMY_MAX, ENEMY_SIZE,
-    SPRITE_SCALE, TIME_STEP,
+    components::{
+        DamageFlash, Enemy, EnemyKind, FireCooldown, FromEnemy, Health, Laser, Player, SpriteSize,
+    },
+    config::GameConfig,
+    pattern::Emitter,
+    rng::{GameRng, RngSystem},
+    state::GameState,
+    wave::WaveProgress,
+    EnemyCount, GameSystem, GameTextures, WinSize,
 };
 
-use self::formation::{Formation, FormationMaker};
+use self::{
+    dive::{dive_movement_system, dive_start_system, Dive},
+    formation::Formation,
+};
 
 pub struct EnemyPlugin;
 
@@ -18,118 +27,205 @@ impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         // app.add_startup_system_to_stage(StartupStage::PostStartup, enemy_spawn_system);
         // app.add_system(enemy_spawn_system);
-        app.insert_resource(FormationMaker::default())
-            .add_system_set(
-                SystemSet::new()
-                    .with_run_criteria(FixedTimestep::step(1.))
-                    .with_system(enemy_spawn_system),
-            )
-            // .add_system(enemy_fire_system);
-            .add_system_set(
-                SystemSet::new()
-                    .with_run_criteria(enemy_fire_criteria)
-                    .with_system(enemy_fire_system),
-            )
-            .add_system(enemy_movement_system);
+        // app.add_system(enemy_fire_system);
+        app.add_system_set(
+            SystemSet::on_update(GameState::Playing)
+                .with_system(
+                    enemy_spawn_system
+                        .label(RngSystem::EnemySpawn)
+                        .label(GameSystem::Spawn)
+                        .after(GameSystem::Input),
+                )
+                .with_system(
+                    enemy_movement_system
+                        .label(GameSystem::Movement)
+                        .after(GameSystem::Spawn),
+                )
+                .with_system(
+                    dive_start_system
+                        .label(RngSystem::EnemyDive)
+                        .label(GameSystem::Spawn)
+                        .after(RngSystem::EnemySpawn)
+                        // its `Dive` inserted before a hit can despawn the enemy
+                        .before(GameSystem::Collision),
+                )
+                .with_system(
+                    dive_movement_system
+                        .label(GameSystem::Movement)
+                        .after(GameSystem::Spawn),
+                )
+                .with_system(
+                    enemy_fire_system
+                        .label(RngSystem::EnemyFire)
+                        .label(GameSystem::Spawn)
+                        .after(RngSystem::EnemyDive),
+                )
+                .with_system(
+                    enemy_damage_flash_system
+                        .label(GameSystem::Cleanup)
+                        .after(GameSystem::Collision),
+                ),
+        );
     }
 }
-fn enemy_fire_criteria() -> ShouldRun {
-    if thread_rng().gen_bool(1. / 60.) {
-        ShouldRun::Yes
-    } else {
-        ShouldRun::No
-    }
+
+/// Cooldown until the next volley, `fire_rate` per second on average with jitter.
+fn fire_cooldown(
+    config: &GameConfig,
+    wave_progress: &WaveProgress,
+    rng: &mut impl Rng,
+) -> FireCooldown {
+    let fire_rate = config.enemy.fire_rate * wave_progress.definition.fire_rate;
+    // not firing at all, check again in a second
+    let interval = if fire_rate > 0. { 1. / fire_rate } else { 1. };
+    let jitter = config.enemy.fire_jitter;
+    let interval = interval * rng.gen_range(1. - jitter..=1. + jitter);
+    FireCooldown(Timer::from_seconds(interval as f32, false))
 }
 
 fn enemy_spawn_system(
     mut commands: Commands,
     mut enemy_count: ResMut<EnemyCount>,
-    mut formation_maker: ResMut<FormationMaker>,
-    time: Res<Time>,
+    mut wave_progress: ResMut<WaveProgress>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
     game_textures: Res<GameTextures>,
     win_size: Res<WinSize>,
 ) {
-    if enemy_count.0 < ENEMY_MAX {
+    if enemy_count.0 < config.enemy.max {
         // let mut rng = thread_rng();
         // let w_span = win_size.w / 2. - 100.;
         // let h_span = win_size.h / 2. - 100.;
         // let x = rng.gen_range(-w_span..w_span);
         // let y = rng.gen_range(-h_span..h_span);
 
-        // get formation and start x/y
-
-        let mut rng = thread_rng();
-        let formation = formation_maker.make(&win_size);
+        // next enemy due in the wave, with its formation and start x/y
+        let wave_speed = wave_progress.definition.speed;
+        let group = match wave_progress.due_group() {
+            Some(group) => group,
+            None => return,
+        };
+        let kind = group.kind;
+        let kind_config = config.enemy.kind(kind);
+        let speed_scale = wave_speed * kind_config.speed;
+        let formation = group
+            .formation
+            .get_or_insert_with(|| {
+                let (template, side) = (&group.template, group.event.side);
+                Formation::new(&win_size, &config, template, side, speed_scale, &mut *rng)
+            })
+            .clone();
+        group.member_spawned();
+        let scale = config.sprite_scale * kind_config.scale;
         let (x, y) = formation.start;
-        let x = if rng.gen_bool(0.5) {x} else {-x};
-
+        let (r, g, b) = kind_config.color;
 
-        commands
-            .spawn_bundle(SpriteBundle {
-                texture: game_textures.enemy.clone(),
-                transform: Transform {
-                    translation: Vec3::new(x, y, 10.),
-                    scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
-                    ..Default::default()
-                },
+        let mut enemy = commands.spawn_bundle(SpriteBundle {
+            texture: game_textures.enemies[&kind].clone(),
+            sprite: Sprite {
+                color: Color::rgb(r, g, b),
                 ..Default::default()
-            })
+            },
+            transform: Transform {
+                translation: Vec3::new(x, y, 10.),
+                scale: Vec3::new(scale, scale, 1.),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        enemy
             .insert(Enemy)
-            .insert(SpriteSize::from(ENEMY_SIZE))
+            .insert(kind)
+            .insert(Health(kind_config.hp))
+            .insert(SpriteSize::from(kind_config.size))
             .insert(formation);
+        if let Some(pattern) = &kind_config.pattern {
+            // lasers leave from under the ship
+            enemy
+                .insert(Emitter::new(pattern.clone(), Vec2::new(0., -15.)))
+                .insert(fire_cooldown(&config, &wave_progress, &mut *rng));
+        }
 
         enemy_count.0 += 1;
     }
 }
 
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn enemy_fire_system(
-    mut commands: Commands,
-    game_textures: Res<GameTextures>,
-    enemy_query: Query<&Transform, With<Enemy>>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    win_size: Res<WinSize>,
+    wave_progress: Res<WaveProgress>,
+    mut rng: ResMut<GameRng>,
+    laser_query: Query<(), (With<Laser>, With<FromEnemy>)>,
+    player_query: Query<&Transform, With<Player>>,
+    mut enemy_query: Query<(&Transform, &mut FireCooldown, &mut Emitter), With<EnemyKind>>,
 ) {
-    for &tf in enemy_query.iter() {
+    let fire_rate = config.enemy.fire_rate * wave_progress.definition.fire_rate;
+    let mut lasers = laser_query.iter().count();
+    let player_position = player_query.get_single().ok().map(|tf| tf.translation);
+
+    for (tf, mut cooldown, mut emitter) in enemy_query.iter_mut() {
+        if !cooldown.0.tick(time.delta()).finished() {
+            continue;
+        }
+        *cooldown = fire_cooldown(&config, &wave_progress, &mut *rng);
+
+        // only on screen, and roughly above the player
         let (x, y) = (tf.translation.x, tf.translation.y);
-        commands
-            .spawn_bundle(SpriteBundle {
-                texture: game_textures.enemy_laser.clone(),
-                transform: Transform {
-                    translation: Vec3::new(x, y - 15., 0.),
-                    rotation: Quat::from_rotation_x(PI),
-                    scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
-                    ..Default::default()
-                },
-                ..Default::default()
-            })
-            .insert(Laser)
-            .insert(SpriteSize::from(ENEMY_LASER_SIZE))
-            .insert(FromEnemy)
-            .insert(Moveable { auto_despawn: true })
-            .insert(Velocity { x: 0., y: -1. });
+        let on_screen = x.abs() < win_size.w / 2. && y.abs() < win_size.h / 2.;
+        let above_player = match player_position {
+            Some(player) => y > player.y && (x - player.x).abs() < config.enemy.fire_range,
+            None => false,
+        };
+        if fire_rate <= 0. || !on_screen || !above_player || lasers >= config.enemy.max_lasers {
+            continue;
+        }
+
+        emitter.trigger();
+        lasers += (emitter.pattern.ways * emitter.pattern.burst) as usize;
+    }
+}
+
+/// Tints enemies hit without being destroyed, back to their color once over.
+fn enemy_damage_flash_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    mut query: Query<(Entity, &EnemyKind, &mut DamageFlash, &mut Sprite)>,
+) {
+    for (entity, kind, mut flash, mut sprite) in query.iter_mut() {
+        if flash.0.tick(time.delta()).finished() {
+            let (r, g, b) = config.enemy.kind(*kind).color;
+            sprite.color = Color::rgb(r, g, b);
+            commands.entity(entity).remove::<DamageFlash>();
+        } else {
+            sprite.color = Color::rgb(1., 0.2, 0.2);
+        }
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn enemy_movement_system(
     time: Res<Time>,
-    mut query: Query<(&mut Transform, &mut Formation), With<Enemy>>,
+    mut query: Query<(&mut Transform, &mut Formation), (With<Enemy>, Without<Dive>)>,
 ) {
+    let delta = time.delta_seconds();
     for (mut transform, mut formation) in query.iter_mut() {
         // current position
         let (x_org, y_org) = (transform.translation.x, transform.translation.y);
         // max distance
-        let max_distance = TIME_STEP * formation.speed;
+        let max_distance = delta * formation.speed;
 
         // let dir: f32 = -1.;
         // let (x_pivot, y_pivot) = (0., 0.);
         // let (x_radius, y_radius) = (200., 130.);
 
-        // 1 counter clockwise ; -1 clockwise
-        let dir: f32 = if formation.start.0 < 0. { 1. } else { -1. };
         let (x_pivot, y_pivot) = formation.pivot;
         let (x_radius, y_radius) = formation.radius;
 
         // computer next angle (base on time for now)
-        let angle = formation.angle
-            + dir * formation.speed * TIME_STEP / (x_radius.min(y_radius) * PI / 2.);
+        let angle = formation.angle + formation.angle_step(delta);
 
         // computer target x/y
         let x_dst = x_radius * angle.cos() + x_pivot;
@@ -154,16 +250,14 @@ fn enemy_movement_system(
         // start rotating the formation angle only when sprite is on or close to ellipse
         if distance < max_distance * formation.speed / 20. {
             formation.angle = angle;
+            formation.orbiting = true;
         }
 
         let translation = &mut transform.translation;
 
         (translation.x, translation.y) = (x, y);
 
-        // translation.x += BASE_SPEED * TIME_STEP / 4.;
-        // translation.y += BASE_SPEED * TIME_STEP / 4.;
+        // translation.x += config.base_speed * delta / 4.;
+        // translation.y += config.base_speed * delta / 4.;
     }
 }
-fn take_time_rng(time: &Time) -> bool {
-        time.seconds_since_startup() as usize % 2 == 1
-    }
\ No newline at end of file
@@ -0,0 +1,204 @@
+use std::time::Duration;
+
+use bevy::{
+    app::{AppExit, ScheduleRunnerPlugin, ScheduleRunnerSettings},
+    core::CorePlugin,
+    input::{keyboard::KeyboardInput, ButtonState, InputPlugin},
+    prelude::*,
+    time::FixedTimesteps,
+};
+
+use crate::{
+    components::EnemyKind, config::GameConfig, high_scores::HighScoreFile, state::GameState,
+    EnemyCount, GameTextures, Wave, WinSize,
+};
+
+/// Settings for running the game loop without a window or GPU.
+///
+/// Parsed from the command line:
+/// `--headless [--frames <n>] [--win-size <w>x<h>] [--step <seconds>]`
+#[derive(Clone)]
+pub struct HeadlessConfig {
+    pub win_size: (f32, f32),
+    /// stop after this many frames (run forever when `None`)
+    pub frames: Option<u32>,
+    /// simulated seconds per frame, so runs do not depend on the host clock
+    pub step: f64,
+    /// press Enter at the menu (off when replaying, the replay presses it itself)
+    pub autostart: bool,
+}
+
+impl Default for HeadlessConfig {
+    fn default() -> Self {
+        Self {
+            win_size: (600., 700.),
+            frames: None,
+            step: 1. / 60.,
+            autostart: true,
+        }
+    }
+}
+
+impl HeadlessConfig {
+    /// Returns `None` when `--headless` is not among the arguments.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Option<Self> {
+        let args: Vec<String> = args.into_iter().collect();
+        if !args.iter().any(|arg| arg == "--headless") {
+            return None;
+        }
+
+        let mut config = HeadlessConfig::default();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--frames" => {
+                    config.frames = iter.next().and_then(|val| val.parse().ok());
+                }
+                "--win-size" => {
+                    if let Some((w, h)) = iter.next().and_then(|val| val.split_once('x')) {
+                        if let (Ok(w), Ok(h)) = (w.parse(), h.parse()) {
+                            config.win_size = (w, h);
+                        }
+                    }
+                }
+                "--replay" => {
+                    config.autostart = false;
+                }
+                "--step" => {
+                    if let Some(step) = iter.next().and_then(|val| val.parse().ok()) {
+                        config.step = step;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(config)
+    }
+}
+
+/// Counts the frames simulated so far in headless mode.
+pub struct HeadlessFrames(pub u32);
+
+/// Replaces `DefaultPlugins` and the windowed `setup_system`.
+pub struct HeadlessPlugin(pub HeadlessConfig);
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        // run frames back to back, no need to wait for a display
+        // (MinimalPlugins without TimePlugin, `Time` is driven by the frame count)
+        app.insert_resource(ScheduleRunnerSettings::run_loop(Duration::ZERO))
+            .insert_resource(self.0.clone())
+            .insert_resource(HeadlessFrames(0))
+            // simulated runs stay out of the player's high-score table
+            .insert_resource(HighScoreFile(None))
+            .add_plugin(CorePlugin)
+            .add_plugin(ScheduleRunnerPlugin)
+            .add_plugin(InputPlugin)
+            .init_resource::<Time>()
+            .init_resource::<FixedTimesteps>()
+            .add_system_to_stage(CoreStage::First, headless_time_system)
+            .add_startup_system(headless_setup_system)
+            .add_system_set(SystemSet::on_enter(GameState::Menu).with_system(headless_start_system))
+            .add_system_to_stage(CoreStage::PostUpdate, headless_frame_limit_system);
+    }
+}
+
+fn headless_setup_system(
+    mut commands: Commands,
+    config: Res<HeadlessConfig>,
+    game_config: Res<GameConfig>,
+) {
+    // add WinSize resource (no window to capture it from)
+    let (w, h) = config.win_size;
+    commands.insert_resource(WinSize { w, h });
+
+    // add GameTextures resource with placeholder handles
+    let game_textures = GameTextures {
+        player: Handle::default(),
+        weapons: vec![Handle::default(); game_config.player.weapons.len()],
+        enemies: EnemyKind::ALL
+            .into_iter()
+            .map(|kind| (kind, Handle::default()))
+            .collect(),
+        enemy_laser: Handle::default(),
+        boss: Handle::default(),
+        explosion: Handle::default(),
+        font: Handle::default(),
+    };
+    commands.insert_resource(game_textures);
+    commands.insert_resource(EnemyCount(0));
+    commands.insert_resource(Wave(1));
+}
+
+/// Nobody is there to press Enter, press it for them. Through the input
+/// events like a real key, so a `--record` made meanwhile has the key press
+/// that starts the game and plays back the same.
+fn headless_start_system(config: Res<HeadlessConfig>, mut keys: EventWriter<KeyboardInput>) {
+    if !config.autostart {
+        return;
+    }
+    for state in [ButtonState::Pressed, ButtonState::Released] {
+        keys.send(KeyboardInput {
+            scan_code: 0,
+            key_code: Some(KeyCode::Return),
+            state,
+        });
+    }
+}
+
+fn headless_time_system(
+    config: Res<HeadlessConfig>,
+    frames: Res<HeadlessFrames>,
+    mut time: ResMut<Time>,
+) {
+    let elapsed = Duration::from_secs_f64(config.step * frames.0 as f64);
+    let instant = time.startup() + elapsed;
+    time.update_with_instant(instant);
+}
+
+fn headless_frame_limit_system(
+    config: Res<HeadlessConfig>,
+    mut frames: ResMut<HeadlessFrames>,
+    mut exit: EventWriter<AppExit>,
+) {
+    frames.0 += 1;
+    if let Some(max) = config.frames {
+        if frames.0 >= max {
+            exit.send(AppExit);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{replay::replay_summary, rng::GameRng, score::Score, GamePlugin, PlayerState};
+
+    /// Plays `frames` frames from `seed` without input, returns the final summary.
+    fn run(seed: u64, frames: u32) -> Vec<(String, String)> {
+        let mut app = App::new();
+        app.insert_resource(GameRng::with_seed(seed))
+            .add_plugin(HeadlessPlugin(HeadlessConfig::default()))
+            .add_plugin(GamePlugin);
+        for _ in 0..frames {
+            app.update();
+        }
+
+        let world = &app.world;
+        replay_summary(
+            frames as usize,
+            world.resource::<EnemyCount>(),
+            world.resource::<PlayerState>(),
+            world.resource::<Score>(),
+        )
+    }
+
+    #[test]
+    fn same_seed_same_game() {
+        // long enough for enemies to spawn, fire and take the player's lives
+        let frames = 60 * 60;
+        for seed in [1, 3, 5, 7] {
+            assert_eq!(run(seed, frames), run(seed, frames), "seed {seed}");
+        }
+    }
+}
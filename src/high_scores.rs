@@ -0,0 +1,394 @@
+use std::{
+    cmp::Reverse,
+    env, fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    rng::GameRng,
+    score::{HighScore, Score},
+    state::{spawn_state_text, state_text_cleanup_system, GameState},
+    GameTextures, Wave,
+};
+
+/// Environment variable overriding the high-score file location.
+pub const HIGH_SCORES_ENV: &str = "GAME_HIGH_SCORES";
+
+const HIGH_SCORES_MAX: usize = 10;
+const INITIALS_LEN: usize = 3;
+
+// region: --- High Score Table
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HighScoreEntry {
+    pub initials: String,
+    pub score: u32,
+    /// `YYYY-MM-DD` (UTC)
+    pub date: String,
+    pub wave: u32,
+    pub seed: u64,
+}
+
+/// Top scores, best first.
+#[derive(Default)]
+pub struct HighScoreTable {
+    pub entries: Vec<HighScoreEntry>,
+}
+
+impl HighScoreTable {
+    /// Missing or unreadable files give an empty table.
+    pub fn load(path: &Path) -> Self {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(_) => return Self::default(),
+        };
+        match ron::from_str::<Vec<HighScoreEntry>>(&content) {
+            Ok(mut entries) => {
+                entries.sort_by_key(|entry| Reverse(entry.score));
+                entries.truncate(HIGH_SCORES_MAX);
+                Self { entries }
+            }
+            Err(err) => {
+                warn!("ignoring corrupt high-score file {path:?}: {err}");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+        let content = ron::ser::to_string_pretty(&self.entries, Default::default())
+            .map_err(|err| err.to_string())?;
+        fs::write(path, content).map_err(|err| err.to_string())
+    }
+
+    pub fn best(&self) -> u32 {
+        self.entries.first().map(|entry| entry.score).unwrap_or(0)
+    }
+
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.entries.len() < HIGH_SCORES_MAX
+                || self.entries.iter().any(|entry| score > entry.score))
+    }
+
+    pub fn insert(&mut self, entry: HighScoreEntry) {
+        let index = self
+            .entries
+            .iter()
+            .position(|other| entry.score > other.score)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(index, entry);
+        self.entries.truncate(HIGH_SCORES_MAX);
+    }
+
+    /// One line per entry, for the menu screen.
+    pub fn lines(&self) -> String {
+        self.entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                format!(
+                    "{:>2}. {} {:>8}  W{:<2} {}",
+                    i + 1,
+                    entry.initials,
+                    entry.score,
+                    entry.wave,
+                    entry.date
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Where the table is stored, `None` keeps it in memory only.
+pub struct HighScoreFile(pub Option<PathBuf>);
+
+impl HighScoreFile {
+    /// `--high-scores <path>` from the command line.
+    pub fn path_from_args(args: impl IntoIterator<Item = String>) -> Option<PathBuf> {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--high-scores" {
+                return args.next().map(PathBuf::from);
+            }
+        }
+        None
+    }
+}
+
+impl Default for HighScoreFile {
+    /// `GAME_HIGH_SCORES` if set, otherwise the platform data directory.
+    fn default() -> Self {
+        if let Some(path) = env::var_os(HIGH_SCORES_ENV) {
+            return Self(Some(path.into()));
+        }
+
+        let data_dir = if cfg!(windows) {
+            env::var_os("APPDATA").map(PathBuf::from)
+        } else if cfg!(target_os = "macos") {
+            env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+        } else {
+            env::var_os("XDG_DATA_HOME").map(PathBuf::from).or_else(|| {
+                env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+            })
+        };
+        Self(data_dir.map(|dir| dir.join("rust_invaders").join("high_scores.ron")))
+    }
+}
+
+// endregion: --- High Score Table
+
+// region: --- Name Entry
+
+/// Initials being typed after a game that made it into the table.
+struct NameEntry {
+    initials: [u8; INITIALS_LEN],
+    cursor: usize,
+}
+
+impl NameEntry {
+    fn text(&self) -> String {
+        let letters: Vec<String> = self
+            .initials
+            .iter()
+            .enumerate()
+            .map(|(i, letter)| {
+                if i == self.cursor {
+                    format!("[{}]", *letter as char)
+                } else {
+                    format!(" {} ", *letter as char)
+                }
+            })
+            .collect();
+        format!(
+            "NEW HIGH SCORE\n\n{}\n\nUp/Down letter, Enter confirm",
+            letters.join("")
+        )
+    }
+}
+
+#[derive(Component)]
+struct NameEntryText;
+
+// endregion: --- Name Entry
+
+pub struct HighScorePlugin;
+
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HighScoreFile>()
+            .add_startup_system(high_score_load_system)
+            .add_system_set(
+                SystemSet::on_enter(GameState::NameEntry).with_system(name_entry_setup_system),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::NameEntry).with_system(name_entry_input_system),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::NameEntry).with_system(state_text_cleanup_system),
+            );
+    }
+}
+
+fn high_score_load_system(mut commands: Commands, file: Res<HighScoreFile>) {
+    let table = match &file.0 {
+        Some(path) => HighScoreTable::load(path),
+        None => HighScoreTable::default(),
+    };
+    commands.insert_resource(HighScore(table.best()));
+    commands.insert_resource(table);
+}
+
+fn name_entry_setup_system(mut commands: Commands, game_textures: Res<GameTextures>) {
+    let name_entry = NameEntry {
+        initials: [b'A'; INITIALS_LEN],
+        cursor: 0,
+    };
+    let entity = spawn_state_text(&mut commands, &game_textures, &name_entry.text());
+    commands.entity(entity).insert(NameEntryText);
+    commands.insert_resource(name_entry);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn name_entry_input_system(
+    mut kb: ResMut<Input<KeyCode>>,
+    mut state: ResMut<State<GameState>>,
+    mut name_entry: ResMut<NameEntry>,
+    mut table: ResMut<HighScoreTable>,
+    file: Res<HighScoreFile>,
+    score: Res<Score>,
+    wave: Res<Wave>,
+    rng: Res<GameRng>,
+    mut query: Query<&mut Text, With<NameEntryText>>,
+) {
+    let cursor = name_entry.cursor;
+    if kb.just_pressed(KeyCode::Up) {
+        let letter = name_entry.initials[cursor];
+        name_entry.initials[cursor] = if letter == b'Z' { b'A' } else { letter + 1 };
+    } else if kb.just_pressed(KeyCode::Down) {
+        let letter = name_entry.initials[cursor];
+        name_entry.initials[cursor] = if letter == b'A' { b'Z' } else { letter - 1 };
+    } else if kb.just_pressed(KeyCode::Left) {
+        name_entry.cursor = cursor.saturating_sub(1);
+    } else if kb.just_pressed(KeyCode::Right) {
+        name_entry.cursor = (cursor + 1).min(INITIALS_LEN - 1);
+    } else if kb.just_pressed(KeyCode::Return) {
+        kb.reset(KeyCode::Return);
+        if cursor + 1 < INITIALS_LEN {
+            name_entry.cursor += 1;
+        } else {
+            table.insert(HighScoreEntry {
+                initials: String::from_utf8_lossy(&name_entry.initials).into_owned(),
+                score: score.value,
+                date: today(),
+                wave: wave.0,
+                seed: rng.seed(),
+            });
+            if let Some(path) = &file.0 {
+                if let Err(err) = table.save(path) {
+                    warn!("failed to save high scores to {path:?}: {err}");
+                }
+            }
+            state.set(GameState::Menu).unwrap();
+            return;
+        }
+    } else {
+        return;
+    }
+
+    for mut text in query.iter_mut() {
+        text.sections[0].value = name_entry.text();
+    }
+}
+
+/// Current UTC date as `YYYY-MM-DD`.
+fn today() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+
+    // days since 1970-01-01 to civil date (Howard Hinnant's algorithm)
+    let z = (secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(score: u32) -> HighScoreEntry {
+        HighScoreEntry {
+            initials: "AAA".to_string(),
+            score,
+            date: "2026-01-01".to_string(),
+            wave: 1,
+            seed: 0,
+        }
+    }
+
+    /// File in the temp folder, removed when dropped.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            let file_name = format!("rust_invaders_{}_{name}.ron", std::process::id());
+            Self(env::temp_dir().join(file_name))
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn scores(table: &HighScoreTable) -> Vec<u32> {
+        table.entries.iter().map(|entry| entry.score).collect()
+    }
+
+    #[test]
+    fn missing_file_gives_an_empty_table() {
+        let file = TempFile::new("missing");
+        assert!(HighScoreTable::load(&file.0).entries.is_empty());
+    }
+
+    #[test]
+    fn corrupt_file_gives_an_empty_table() {
+        let file = TempFile::new("corrupt");
+        fs::write(&file.0, "[(initials: \"AAA\", score: ").unwrap();
+        assert!(HighScoreTable::load(&file.0).entries.is_empty());
+    }
+
+    #[test]
+    fn load_sorts_and_truncates() {
+        let file = TempFile::new("unsorted");
+        let entries: Vec<HighScoreEntry> = (1..=12).map(|i| entry(i * 100)).collect();
+        let content = ron::ser::to_string(&entries).unwrap();
+        fs::write(&file.0, content).unwrap();
+
+        let table = HighScoreTable::load(&file.0);
+        assert_eq!(
+            scores(&table),
+            (3..=12).rev().map(|i| i * 100).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let file = TempFile::new("round_trip");
+        let mut table = HighScoreTable::default();
+        table.insert(entry(300));
+        table.insert(entry(500));
+        table.save(&file.0).unwrap();
+
+        let loaded = HighScoreTable::load(&file.0);
+        assert_eq!(scores(&loaded), vec![500, 300]);
+        assert_eq!(loaded.best(), 500);
+    }
+
+    #[test]
+    fn insert_keeps_the_best_ones() {
+        let mut table = HighScoreTable::default();
+        for score in [300, 100, 200, 200] {
+            table.insert(entry(score));
+        }
+        // ties go after the earlier entry
+        assert_eq!(scores(&table), vec![300, 200, 200, 100]);
+
+        for score in 1..=HIGH_SCORES_MAX as u32 {
+            table.insert(entry(1000 + score));
+        }
+        assert_eq!(table.entries.len(), HIGH_SCORES_MAX);
+        assert_eq!(table.entries.last().unwrap().score, 1001);
+    }
+
+    #[test]
+    fn qualifies() {
+        let mut table = HighScoreTable::default();
+        assert!(!table.qualifies(0));
+        assert!(table.qualifies(1));
+
+        for score in 1..=HIGH_SCORES_MAX as u32 {
+            table.insert(entry(score * 100));
+        }
+        assert!(!table.qualifies(100));
+        assert!(table.qualifies(101));
+    }
+}
@@ -0,0 +1,346 @@
+use bevy::prelude::*;
+
+use crate::{
+    boss::BossHealth,
+    components::{Player, Weapon},
+    config::GameConfig,
+    score::{HighScore, Score},
+    state::GameState,
+    GameSystem, GameTextures, PlayerState, Wave,
+};
+
+const HUD_FONT_SIZE: f32 = 20.;
+const HUD_PADDING: f32 = 8.;
+/// scale of the player sprite used as a life icon
+const LIFE_ICON_SCALE: f32 = 0.2;
+const BOSS_BAR_TOP: f32 = 40.;
+const BOSS_BAR_HEIGHT: f32 = 10.;
+const HEAT_BAR_WIDTH: f32 = 120.;
+const HEAT_BAR_HEIGHT: f32 = 8.;
+const HEAT_COLOR: Color = Color::rgb(1., 0.6, 0.1);
+const OVERHEAT_COLOR: Color = Color::rgb(1., 0.1, 0.1);
+
+// region: --- HUD Components
+
+#[derive(Component)]
+struct Hud;
+
+#[derive(Component)]
+struct HudScore;
+
+#[derive(Component)]
+struct HudHighScore;
+
+#[derive(Component)]
+struct HudWave;
+
+/// Smart bombs in stock.
+#[derive(Component)]
+struct HudBombs;
+
+/// Row of life icons, with the number of icons it currently shows.
+#[derive(Component)]
+struct HudLives(u32);
+
+/// Boss health bar, only displayed during a boss fight.
+#[derive(Component)]
+struct HudBossBar;
+
+#[derive(Component)]
+struct HudBossFill;
+
+/// Weapon heat meter, only displayed with `player.heat` set and a ship on screen.
+#[derive(Component)]
+struct HudHeatBar;
+
+#[derive(Component)]
+struct HudHeatFill;
+
+// endregion: --- HUD Components
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(GameState::Playing).with_system(hud_setup_system))
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .label(GameSystem::Hud)
+                    .after(GameSystem::Cleanup)
+                    .with_system(hud_score_system)
+                    .with_system(hud_wave_system)
+                    .with_system(hud_lives_system)
+                    .with_system(hud_bombs_system)
+                    .with_system(hud_boss_system)
+                    .with_system(hud_heat_system),
+            )
+            .add_system_set(SystemSet::on_exit(GameState::Playing).with_system(hud_cleanup_system));
+    }
+}
+
+fn hud_text(game_textures: &GameTextures, value: String) -> TextBundle {
+    TextBundle::from_section(
+        value,
+        TextStyle {
+            font: game_textures.font.clone(),
+            font_size: HUD_FONT_SIZE,
+            color: Color::WHITE,
+        },
+    )
+}
+
+fn hud_setup_system(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    score: Res<Score>,
+    high_score: Res<HighScore>,
+    wave: Res<Wave>,
+    player_state: Res<PlayerState>,
+) {
+    // full width bar pinned to the top, flex layout follows window resizes
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Auto),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(0.),
+                    left: Val::Px(0.),
+                    ..Default::default()
+                },
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(HUD_PADDING)),
+                ..Default::default()
+            },
+            color: Color::NONE.into(),
+            ..Default::default()
+        })
+        .insert(Hud)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(hud_text(&game_textures, format!("SCORE {}", score.value)))
+                .insert(HudScore);
+            parent
+                .spawn_bundle(hud_text(&game_textures, format!("WAVE {}", wave.0)))
+                .insert(HudWave);
+            parent
+                .spawn_bundle(hud_text(&game_textures, format!("HI {}", high_score.0)))
+                .insert(HudHighScore);
+            parent
+                .spawn_bundle(hud_text(
+                    &game_textures,
+                    format!("BOMBS {}", player_state.bombs),
+                ))
+                .insert(HudBombs);
+            parent
+                .spawn_bundle(NodeBundle {
+                    color: Color::NONE.into(),
+                    ..Default::default()
+                })
+                .insert(HudLives(0));
+        });
+
+    // centered under the top bar, hidden until a boss shows up
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(60.), Val::Px(BOSS_BAR_HEIGHT)),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(BOSS_BAR_TOP),
+                    left: Val::Percent(20.),
+                    ..Default::default()
+                },
+                display: Display::None,
+                ..Default::default()
+            },
+            color: Color::rgba(1., 1., 1., 0.2).into(),
+            ..Default::default()
+        })
+        .insert(Hud)
+        .insert(HudBossBar)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                        ..Default::default()
+                    },
+                    color: Color::rgb(0.9, 0.2, 0.2).into(),
+                    ..Default::default()
+                })
+                .insert(HudBossFill);
+        });
+
+    // bottom left corner, hidden while there is no heat to show
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Px(HEAT_BAR_WIDTH), Val::Px(HEAT_BAR_HEIGHT)),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    bottom: Val::Px(HUD_PADDING),
+                    left: Val::Px(HUD_PADDING),
+                    ..Default::default()
+                },
+                display: Display::None,
+                ..Default::default()
+            },
+            color: Color::rgba(1., 1., 1., 0.2).into(),
+            ..Default::default()
+        })
+        .insert(Hud)
+        .insert(HudHeatBar)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(0.), Val::Percent(100.)),
+                        ..Default::default()
+                    },
+                    color: HEAT_COLOR.into(),
+                    ..Default::default()
+                })
+                .insert(HudHeatFill);
+        });
+}
+
+fn hud_score_system(
+    score: Res<Score>,
+    high_score: Res<HighScore>,
+    mut score_query: Query<&mut Text, (With<HudScore>, Without<HudHighScore>)>,
+    mut high_score_query: Query<&mut Text, (With<HudHighScore>, Without<HudScore>)>,
+) {
+    if score.is_changed() {
+        for mut text in score_query.iter_mut() {
+            text.sections[0].value = format!("SCORE {}", score.value);
+        }
+    }
+    if high_score.is_changed() {
+        for mut text in high_score_query.iter_mut() {
+            text.sections[0].value = format!("HI {}", high_score.0);
+        }
+    }
+}
+
+fn hud_wave_system(wave: Res<Wave>, mut query: Query<&mut Text, With<HudWave>>) {
+    if wave.is_changed() {
+        for mut text in query.iter_mut() {
+            text.sections[0].value = format!("WAVE {}", wave.0);
+        }
+    }
+}
+
+fn hud_lives_system(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    game_textures: Res<GameTextures>,
+    player_state: Res<PlayerState>,
+    mut query: Query<(Entity, &mut HudLives)>,
+) {
+    // ships in reserve, the one on screen is not shown
+    let reserve = if player_state.on {
+        player_state.lives.saturating_sub(1)
+    } else {
+        player_state.lives
+    };
+
+    for (entity, mut hud_lives) in query.iter_mut() {
+        if hud_lives.0 == reserve {
+            continue;
+        }
+        hud_lives.0 = reserve;
+
+        let (w, h) = config.player.size;
+        let icon_size = Size::new(Val::Px(w * LIFE_ICON_SCALE), Val::Px(h * LIFE_ICON_SCALE));
+        let mut lives_row = commands.entity(entity);
+        lives_row.despawn_descendants();
+        lives_row.with_children(|parent| {
+            for _ in 0..reserve {
+                parent.spawn_bundle(ImageBundle {
+                    style: Style {
+                        size: icon_size,
+                        margin: UiRect::all(Val::Px(2.)),
+                        ..Default::default()
+                    },
+                    image: game_textures.player.clone().into(),
+                    ..Default::default()
+                });
+            }
+        });
+    }
+}
+
+fn hud_bombs_system(player_state: Res<PlayerState>, mut query: Query<&mut Text, With<HudBombs>>) {
+    if player_state.is_changed() {
+        for mut text in query.iter_mut() {
+            text.sections[0].value = format!("BOMBS {}", player_state.bombs);
+        }
+    }
+}
+
+fn hud_boss_system(
+    boss_health: Option<Res<BossHealth>>,
+    mut bar_query: Query<&mut Style, (With<HudBossBar>, Without<HudBossFill>)>,
+    mut fill_query: Query<&mut Style, (With<HudBossFill>, Without<HudBossBar>)>,
+) {
+    let display = if boss_health.is_some() {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    for mut style in bar_query.iter_mut() {
+        if style.display != display {
+            style.display = display;
+        }
+    }
+
+    if let Some(boss_health) = boss_health.filter(|boss_health| boss_health.is_changed()) {
+        let percent = 100. * boss_health.hp as f32 / boss_health.total.max(1) as f32;
+        for mut style in fill_query.iter_mut() {
+            style.size.width = Val::Percent(percent);
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn hud_heat_system(
+    config: Res<GameConfig>,
+    weapon_query: Query<&Weapon, With<Player>>,
+    mut bar_query: Query<&mut Style, (With<HudHeatBar>, Without<HudHeatFill>)>,
+    mut fill_query: Query<(&mut Style, &mut UiColor), (With<HudHeatFill>, Without<HudHeatBar>)>,
+) {
+    let weapon = weapon_query
+        .get_single()
+        .ok()
+        .filter(|_| config.player.heat.is_some());
+    let display = if weapon.is_some() {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    for mut style in bar_query.iter_mut() {
+        if style.display != display {
+            style.display = display;
+        }
+    }
+
+    if let Some(weapon) = weapon {
+        let color = if weapon.overheated {
+            OVERHEAT_COLOR
+        } else {
+            HEAT_COLOR
+        };
+        for (mut style, mut ui_color) in fill_query.iter_mut() {
+            style.size.width = Val::Percent(100. * weapon.heat);
+            ui_color.0 = color;
+        }
+    }
+}
+
+fn hud_cleanup_system(mut commands: Commands, query: Query<Entity, With<Hud>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,898 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::asset::AssetServerSettings;
+use bevy::ecs::system::SystemParam;
+use bevy::math::Vec3Swizzles;
+use bevy::window::WindowResized;
+use bevy::{prelude::*, sprite::collide_aabb::collide};
+use bomb::{BombDetonated, BombPlugin};
+use boss::BossPlugin;
+use components::{
+    Armor, Damage, DamageFlash, Enemy, EnemyKind, Explosion, ExplosionTimer, ExplosionToSpawn,
+    FromEnemy, FromPlayer, Health, Laser, Moveable, Piercing, Player, SpriteSize, Velocity, Weapon,
+};
+use config::{GameConfig, PlayerConfig};
+pub use enemy::{formation::Formation, EnemyPlugin};
+use high_scores::HighScorePlugin;
+use hud::HudPlugin;
+use pattern::PatternPlugin;
+pub use player::PlayerPlugin;
+use power_up::PowerUpPlugin;
+use reload::ReloadPlugin;
+use rng::{GameRng, RngSystem};
+use score::{EnemyKilled, Score, ScorePlugin};
+use state::{GameState, StatePlugin};
+use wave::{script::WaveScript, WavePlugin};
+
+pub mod bomb;
+pub mod boss;
+pub mod components;
+pub mod config;
+mod enemy;
+pub mod headless;
+pub mod high_scores;
+pub mod hud;
+pub mod pattern;
+mod player;
+pub mod power_up;
+pub mod reload;
+pub mod replay;
+pub mod rng;
+pub mod score;
+pub mod state;
+pub mod wave;
+
+// region: --- Resources
+
+pub struct WinSize {
+    pub w: f32,
+    pub h: f32,
+}
+
+pub struct GameTextures {
+    pub player: Handle<Image>,
+    /// projectile sprite of each weapon level
+    pub weapons: Vec<Handle<Image>>,
+    /// one sprite per `EnemyKind`
+    pub enemies: HashMap<EnemyKind, Handle<Image>>,
+    pub enemy_laser: Handle<Image>,
+    pub boss: Handle<Image>,
+    pub explosion: Handle<TextureAtlas>,
+    pub font: Handle<Font>,
+}
+
+pub struct EnemyCount(pub u32);
+
+/// Number of the wave being played, starting at 1.
+pub struct Wave(pub u32);
+
+/// Seconds played in the current game, standing still while paused, so the
+/// respawn delay and invulnerability do not run out behind the pause screen.
+#[derive(Default)]
+pub struct GameClock(pub f64);
+
+pub struct PlayerState {
+    pub on: bool,
+    /// `GameClock` times, -1 when not yet
+    pub last_shot: f64,
+    pub last_spawn: f64,
+    /// lives left, including the ship currently on screen
+    pub lives: u32,
+    pub kills: u32,
+    /// level the next ship starts its `Weapon` at
+    pub weapon_level: usize,
+    /// smart bombs in stock
+    pub bombs: u32,
+    /// no hits until then (`GameClock`), on top of the spawn invulnerability
+    pub invulnerable_until: f64,
+    next_extra_life: usize,
+}
+
+impl Default for PlayerState {
+    fn default() -> Self {
+        Self::new(&PlayerConfig::default())
+    }
+}
+
+impl PlayerState {
+    /// State at the start of a game.
+    pub fn new(config: &PlayerConfig) -> Self {
+        Self {
+            on: false,
+            last_shot: -1.,
+            last_spawn: -1.,
+            lives: config.starting_lives,
+            kills: 0,
+            weapon_level: 0,
+            bombs: config.bomb.stock,
+            invulnerable_until: -1.,
+            next_extra_life: 0,
+        }
+    }
+    /// Ship lost with its weapon at `weapon_level`, the next one gets a level less.
+    pub fn shot(&mut self, time: f64, weapon_level: usize) {
+        self.on = false;
+        self.last_shot = time;
+        self.lives = self.lives.saturating_sub(1);
+        self.weapon_level = weapon_level.saturating_sub(1);
+    }
+    pub fn spawned(&mut self, time: f64) {
+        self.on = true;
+        self.last_shot = -1.;
+        self.last_spawn = time;
+    }
+    pub fn invulnerable(&self, time: f64, config: &PlayerConfig) -> bool {
+        self.on
+            && (time < self.last_spawn + config.invulnerable_duration
+                || time < self.invulnerable_until)
+    }
+    /// Counts a kill, returns true when it earned an extra life.
+    pub fn enemy_killed(&mut self, config: &PlayerConfig) -> bool {
+        self.kills += 1;
+        match config.extra_life_kills.get(self.next_extra_life) {
+            Some(&threshold) if self.kills >= threshold => {
+                self.next_extra_life += 1;
+                self.lives += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+// endregion: --- Resources
+
+/// Steps of a frame of play, run one after the other.
+///
+/// Left to the executor, systems with no order between them run in a
+/// different order from run to run, and the same seed and inputs would not
+/// play the same game. Systems sharing a step either handle different
+/// entities or are ordered among themselves.
+#[derive(SystemLabel, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GameSystem {
+    /// player steering and firing
+    Input,
+    /// waves, enemies, bosses and the player coming in, dives, enemy fire
+    Spawn,
+    /// everything moved by its velocity, formation, dive or pattern
+    Movement,
+    /// lasers, rams, bombs and pick-ups hitting, destroyed entities despawned
+    Collision,
+    /// explosions, flashes, score and pause once the frame has played out
+    Cleanup,
+    /// the HUD showing where the frame left off
+    Hud,
+}
+
+/// The whole game: player, enemies, movement, collisions and explosions.
+///
+/// Expects `WinSize` and `GameTextures` to be provided, either by
+/// `WindowedPlugin` or by `headless::HeadlessPlugin`.
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        // keep a GameRng / GameConfig inserted beforehand (e.g. from `--seed`)
+        app.init_resource::<GameRng>()
+            .init_resource::<GameConfig>()
+            .init_resource::<GameClock>()
+            // after the frame, once the replay or headless runner has set `Time`
+            .add_system_to_stage(CoreStage::PostUpdate, game_clock_system)
+            .add_plugin(StatePlugin)
+            .add_plugin(ScorePlugin)
+            .add_plugin(HudPlugin)
+            .add_plugin(HighScorePlugin)
+            .add_plugin(PlayerPlugin)
+            .add_plugin(EnemyPlugin)
+            .add_plugin(WavePlugin)
+            .add_plugin(BossPlugin)
+            .add_plugin(PatternPlugin)
+            .add_plugin(PowerUpPlugin)
+            .add_plugin(BombPlugin)
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(
+                        moveable_system
+                            .label(GameSystem::Movement)
+                            .after(GameSystem::Spawn),
+                    )
+                    // kills rolled for power-ups in the same frame
+                    .with_system(
+                        player_laser_hit_enemy_system
+                            .label(GameSystem::Collision)
+                            .after(GameSystem::Movement)
+                            .before(RngSystem::PowerUpDrop),
+                    )
+                    // the boss hit in between, lasers are taken by enemies first
+                    .with_system(
+                        enemy_laser_hit_player_system
+                            .label(GameSystem::Collision)
+                            .after(player_laser_hit_enemy_system),
+                    )
+                    // after both hit systems, so nothing is destroyed twice in a frame
+                    .with_system(
+                        enemy_ram_player_system
+                            .label(GameSystem::Collision)
+                            .after(player_laser_hit_enemy_system)
+                            .after(enemy_laser_hit_player_system)
+                            .before(RngSystem::PowerUpDrop),
+                    )
+                    .with_system(
+                        explosion_to_spawn_system
+                            .label(GameSystem::Cleanup)
+                            .after(GameSystem::Collision),
+                    )
+                    .with_system(
+                        explosion_animation_system
+                            .label(GameSystem::Cleanup)
+                            .after(GameSystem::Collision),
+                    ),
+            );
+    }
+}
+
+/// Window, renderer and sprite assets for playing the game on screen.
+pub struct WindowedPlugin;
+
+impl Plugin for WindowedPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Color::rgb(0.04, 0.04, 0.04))
+            .insert_resource(WindowDescriptor {
+                title: "Rust Invaders!".to_string(),
+                height: 700.,
+                width: 600.,
+                ..Default::default()
+            })
+            // pick up edits of the config and wave files while the game runs
+            .insert_resource(AssetServerSettings {
+                watch_for_changes: true,
+                ..Default::default()
+            })
+            .add_plugins(DefaultPlugins)
+            .add_plugin(ReloadPlugin::<GameConfig>::default())
+            .add_plugin(ReloadPlugin::<WaveScript>::default())
+            .add_startup_system(setup_system)
+            .add_system(win_size_resize_system);
+    }
+}
+
+fn setup_system(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    assert_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut windows: ResMut<Windows>,
+) {
+    // camera
+    commands.spawn_bundle(Camera2dBundle::default());
+
+    // add rectangle
+    // commands.spawn_bundle(SpriteBundle{
+    //     sprite: Sprite {
+    //         color: Color::rgb(0.25,0.25,0.75),
+    //         custom_size: Some(Vec2::new(150.,150.)),
+    //         ..Default::default()
+    //     },
+    //     ..Default::default()
+    // });
+
+    // capture window size
+    let window = windows.get_primary_mut().unwrap();
+    let (win_w, win_h) = (window.width(), window.height());
+
+    // position window
+    // window.set_position(IVec2::new(2780, 4900));
+
+    // add WinSize resource
+    let win_size = WinSize { w: win_w, h: win_h };
+    commands.insert_resource(win_size);
+
+    // create explosion texture atlas
+    let sprites = &config.sprites;
+    let texture_handle = assert_server.load(&sprites.explosion_sheet);
+    let texture_atlas = TextureAtlas::from_grid(texture_handle, Vec2::new(64., 64.), 4, 4);
+    let explosion = texture_atlases.add(texture_atlas);
+
+    // add GameTextures resource
+    let game_textures = GameTextures {
+        player: assert_server.load(&sprites.player),
+        weapons: config
+            .player
+            .weapons
+            .iter()
+            .map(|weapon| assert_server.load(&weapon.sprite))
+            .collect(),
+        enemies: EnemyKind::ALL
+            .into_iter()
+            .map(|kind| (kind, assert_server.load(&config.enemy.kind(kind).sprite)))
+            .collect(),
+        enemy_laser: assert_server.load(&sprites.enemy_laser),
+        boss: assert_server.load(&sprites.boss),
+        explosion,
+        font: assert_server.load(&sprites.font),
+    };
+    commands.insert_resource(game_textures);
+    commands.insert_resource(EnemyCount(0));
+    commands.insert_resource(Wave(1));
+}
+
+fn game_clock_system(time: Res<Time>, state: Res<State<GameState>>, mut clock: ResMut<GameClock>) {
+    if state.current() == &GameState::Playing {
+        clock.0 += time.delta_seconds_f64();
+    }
+}
+
+fn win_size_resize_system(
+    mut win_size: ResMut<WinSize>,
+    mut resized_events: EventReader<WindowResized>,
+) {
+    for event in resized_events.iter() {
+        win_size.w = event.width;
+        win_size.h = event.height;
+    }
+}
+
+fn moveable_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    win_size: Res<WinSize>,
+    mut query: Query<(Entity, &Velocity, &mut Transform, &Moveable)>,
+) {
+    let delta = time.delta_seconds();
+    let speed = config.base_speed;
+    for (entity, velocity, mut transform, moveable) in query.iter_mut() {
+        let translation = &mut transform.translation;
+        translation.x += velocity.x * delta * speed;
+        translation.y += velocity.y * delta * speed;
+
+        if moveable.auto_despawn {
+            const MARGIN: f32 = 200.;
+            if translation.y > win_size.h / 2. + MARGIN
+                || translation.y < -win_size.h / 2. - MARGIN
+                || translation.x > win_size.w / 2. + MARGIN
+                || translation.x < -win_size.w / 2. - MARGIN
+            {
+                // println!("---> despawn {entity:?}");
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+/// Removes a destroyed enemy, counting the kill and leaving an explosion.
+fn destroy_enemy(
+    commands: &mut Commands,
+    entity: Entity,
+    enemy_count: &mut EnemyCount,
+    player_state: &mut PlayerState,
+    config: &GameConfig,
+    enemy_killed: &mut EventWriter<EnemyKilled>,
+    killed: EnemyKilled,
+) {
+    commands.entity(entity).despawn();
+    enemy_count.0 -= 1;
+    player_state.enemy_killed(&config.player);
+    // spawn the explosion
+    commands.spawn().insert(ExplosionToSpawn(killed.position));
+    enemy_killed.send(killed);
+}
+
+/// Enemies that lasers, bombs and rams can hit.
+type EnemyHitQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Transform,
+        &'static SpriteSize,
+        &'static EnemyKind,
+        &'static Formation,
+        &'static mut Health,
+    ),
+    With<Enemy>,
+>;
+
+/// Player lasers, with the enemies already pierced.
+type PlayerLaserQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Transform,
+        &'static SpriteSize,
+        &'static Damage,
+        Option<&'static mut Piercing>,
+    ),
+    (With<Laser>, With<FromPlayer>),
+>;
+
+/// Player lasers and smart bombs against the enemies.
+#[allow(clippy::too_many_arguments)]
+fn player_laser_hit_enemy_system(
+    mut commands: Commands,
+    mut enemy_count: ResMut<EnemyCount>,
+    mut player_state: ResMut<PlayerState>,
+    mut enemy_killed: EventWriter<EnemyKilled>,
+    mut bombs: EventReader<BombDetonated>,
+    config: Res<GameConfig>,
+    mut laser_query: PlayerLaserQuery,
+    mut enemy_query: EnemyHitQuery,
+) {
+    let mut despawned_entities: HashSet<Entity> = HashSet::new();
+
+    // a smart bomb hits every enemy at once
+    let bomb_damage: u32 = bombs.iter().map(|bomb| bomb.damage).sum();
+    if bomb_damage > 0 {
+        for (enemy_entity, enemy_tf, _, enemy_kind, formation, mut health) in enemy_query.iter_mut()
+        {
+            health.0 = health.0.saturating_sub(bomb_damage);
+            if health.0 > 0 {
+                commands.entity(enemy_entity).insert(DamageFlash::default());
+                continue;
+            }
+
+            despawned_entities.insert(enemy_entity);
+            destroy_enemy(
+                &mut commands,
+                enemy_entity,
+                &mut enemy_count,
+                &mut player_state,
+                &config,
+                &mut enemy_killed,
+                EnemyKilled {
+                    position: enemy_tf.translation,
+                    kind: *enemy_kind,
+                    points: config.enemy.kind(*enemy_kind).points,
+                    formation: formation.clone(),
+                },
+            );
+        }
+    }
+
+    for (laser_entity, laser_tf, laser_size, damage, mut piercing) in laser_query.iter_mut() {
+        if despawned_entities.contains(&laser_entity) {
+            continue;
+        }
+
+        let laser_scale = laser_tf.scale.xy();
+
+        for (enemy_entity, enemy_tf, enemy_size, enemy_kind, formation, mut health) in
+            enemy_query.iter_mut()
+        {
+            if despawned_entities.contains(&enemy_entity)
+                || despawned_entities.contains(&laser_entity)
+            {
+                continue;
+            }
+
+            let enemy_scale = enemy_tf.scale.xy();
+
+            let collision = collide(
+                laser_tf.translation,
+                laser_size.0 * laser_scale,
+                enemy_tf.translation,
+                enemy_size.0 * enemy_scale,
+            );
+
+            if collision.is_some() {
+                if let Some(piercing) = piercing.as_mut() {
+                    // goes on, hitting each enemy once
+                    if !piercing.0.insert(enemy_entity) {
+                        continue;
+                    }
+                } else {
+                    // remove laser
+                    commands.entity(laser_entity).despawn();
+                    despawned_entities.insert(laser_entity);
+                }
+
+                health.0 = health.0.saturating_sub(damage.0);
+                if health.0 > 0 {
+                    commands.entity(enemy_entity).insert(DamageFlash::default());
+                    continue;
+                }
+
+                // remove enemy
+                despawned_entities.insert(enemy_entity);
+                destroy_enemy(
+                    &mut commands,
+                    enemy_entity,
+                    &mut enemy_count,
+                    &mut player_state,
+                    &config,
+                    &mut enemy_killed,
+                    EnemyKilled {
+                        position: enemy_tf.translation,
+                        kind: *enemy_kind,
+                        points: config.enemy.kind(*enemy_kind).points,
+                        formation: formation.clone(),
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// The player ship, for lasers and rams.
+type PlayerHitQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Transform,
+        &'static SpriteSize,
+        &'static Weapon,
+        &'static mut Armor,
+    ),
+    With<Player>,
+>;
+
+type EnemyLaserQuery<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static Transform, &'static SpriteSize),
+    (With<Laser>, With<FromEnemy>),
+>;
+
+fn enemy_laser_hit_player_system(
+    mut hits: PlayerHits,
+    laser_query: EnemyLaserQuery,
+    mut player_query: PlayerHitQuery,
+) {
+    // freshly spawned ship, lasers fly through it
+    if hits.invulnerable() {
+        return;
+    }
+
+    if let Ok((player_entitiy, player_tf, player_size, weapon, mut armor)) =
+        player_query.get_single_mut()
+    {
+        let player_scale = player_tf.scale.xy();
+
+        for (laser_entity, laser_tf, laser_size) in laser_query.iter() {
+            let laser_scale = laser_tf.scale.xy();
+
+            let collision = collide(
+                player_tf.translation,
+                player_size.0 * player_scale,
+                laser_tf.translation,
+                laser_size.0 * laser_scale,
+            );
+
+            if collision.is_some() {
+                hits.commands.entity(laser_entity).despawn();
+
+                if hits.hit(player_entitiy, player_tf, weapon, &mut armor) {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// What a hit on the player ship touches, shared by lasers and rams.
+#[derive(SystemParam)]
+struct PlayerHits<'w, 's> {
+    commands: Commands<'w, 's>,
+    player_state: ResMut<'w, PlayerState>,
+    score: ResMut<'w, Score>,
+    config: Res<'w, GameConfig>,
+    clock: Res<'w, GameClock>,
+}
+
+impl PlayerHits<'_, '_> {
+    fn invulnerable(&self) -> bool {
+        self.player_state
+            .invulnerable(self.clock.0, &self.config.player)
+    }
+
+    /// Takes a hit on the player ship, returns true when the ship is lost.
+    fn hit(
+        &mut self,
+        player_entity: Entity,
+        player_tf: &Transform,
+        weapon: &Weapon,
+        armor: &mut Armor,
+    ) -> bool {
+        // any hit breaks the chain, even one the shield takes
+        self.score.break_chain();
+
+        // the shield or the hull took it
+        let shielded = armor.shield > 0;
+        if !armor.hit() {
+            if !shielded {
+                self.commands
+                    .entity(player_entity)
+                    .insert(DamageFlash::default());
+            }
+            return false;
+        }
+
+        // the shield sprite goes along
+        self.commands.entity(player_entity).despawn_recursive();
+        self.player_state.shot(self.clock.0, weapon.level);
+
+        self.commands
+            .spawn()
+            .insert(ExplosionToSpawn(player_tf.translation));
+        true
+    }
+}
+
+/// Enemy ships flying into the player, damaging both.
+fn enemy_ram_player_system(
+    mut hits: PlayerHits,
+    mut enemy_count: ResMut<EnemyCount>,
+    mut enemy_killed: EventWriter<EnemyKilled>,
+    mut player_query: PlayerHitQuery,
+    mut enemy_query: EnemyHitQuery,
+) {
+    // freshly spawned or just rammed, enemies fly through it
+    if hits.invulnerable() {
+        return;
+    }
+
+    let (player_entity, player_tf, player_size, weapon, mut armor) =
+        match player_query.get_single_mut() {
+            Ok(player) => player,
+            Err(_) => return,
+        };
+    // already lost to a laser this frame
+    if armor.hp == 0 {
+        return;
+    }
+
+    for (enemy_entity, enemy_tf, enemy_size, enemy_kind, formation, mut health) in
+        enemy_query.iter_mut()
+    {
+        // destroyed by a laser or a bomb this frame
+        if health.0 == 0 {
+            continue;
+        }
+
+        let collision = collide(
+            player_tf.translation,
+            player_size.0 * player_tf.scale.xy(),
+            enemy_tf.translation,
+            enemy_size.0 * enemy_tf.scale.xy(),
+        );
+        if collision.is_none() {
+            continue;
+        }
+
+        let config = &hits.config;
+        health.0 = health.0.saturating_sub(config.enemy.ram_damage);
+        if health.0 > 0 {
+            hits.commands
+                .entity(enemy_entity)
+                .insert(DamageFlash::default());
+        } else {
+            destroy_enemy(
+                &mut hits.commands,
+                enemy_entity,
+                &mut enemy_count,
+                &mut hits.player_state,
+                config,
+                &mut enemy_killed,
+                EnemyKilled {
+                    position: enemy_tf.translation,
+                    kind: *enemy_kind,
+                    points: config.enemy.kind(*enemy_kind).points,
+                    formation: formation.clone(),
+                },
+            );
+        }
+
+        if !hits.hit(player_entity, player_tf, weapon, &mut armor) {
+            // one ram at a time, an enemy still in the way does not hit again next frame
+            hits.player_state.invulnerable_until =
+                hits.clock.0 + hits.config.player.ram_invulnerable_duration;
+        }
+        break;
+    }
+}
+
+fn explosion_to_spawn_system(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    query: Query<(Entity, &ExplosionToSpawn)>,
+) {
+    for (explosion_to_spawn_entity, explosion_to_spawn) in query.iter() {
+        commands
+            .spawn_bundle(SpriteSheetBundle {
+                texture_atlas: game_textures.explosion.clone(),
+                transform: Transform {
+                    translation: explosion_to_spawn.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(Explosion)
+            .insert(ExplosionTimer::default());
+
+        commands.entity(explosion_to_spawn_entity).despawn();
+    }
+}
+
+fn explosion_animation_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    mut query: Query<(Entity, &mut ExplosionTimer, &mut TextureAtlasSprite), With<Explosion>>,
+) {
+    for (entity, mut timer, mut sprite) in query.iter_mut() {
+        timer.0.tick(time.delta());
+        if timer.0.finished() {
+            sprite.index += 1;
+            if sprite.index >= config.sprites.explosion_len {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn world() -> World {
+        let config = GameConfig::default();
+        let mut world = World::new();
+        world.insert_resource(EnemyCount(0));
+        world.insert_resource(PlayerState::new(&config.player));
+        world.insert_resource(Score::default());
+        world.insert_resource(GameClock::default());
+        world.insert_resource(config);
+        world.insert_resource(Events::<EnemyKilled>::default());
+        world.insert_resource(Events::<BombDetonated>::default());
+        world
+    }
+
+    fn spawn_enemy(world: &mut World, kind: EnemyKind) -> Entity {
+        let hp = world.resource::<GameConfig>().enemy.kind(kind).hp;
+        world.resource_mut::<EnemyCount>().0 += 1;
+        world
+            .spawn()
+            .insert(Enemy)
+            .insert(kind)
+            .insert(Health(hp))
+            .insert(SpriteSize::from((20., 20.)))
+            .insert(Transform::default())
+            .insert(Formation {
+                start: (0., 0.),
+                radius: (0., 0.),
+                pivot: (0., 0.),
+                speed: 0.,
+                angle: 0.,
+                orbiting: true,
+            })
+            .id()
+    }
+
+    fn spawn_laser(world: &mut World) {
+        world
+            .spawn()
+            .insert(Laser)
+            .insert(FromPlayer)
+            .insert(Damage(1))
+            .insert(SpriteSize::from((5., 5.)))
+            .insert(Transform::default());
+    }
+
+    fn spawn_player(world: &mut World, hp: u32) -> Entity {
+        // past the spawn invulnerability
+        world.resource_mut::<GameClock>().0 = 10.;
+        world.resource_mut::<PlayerState>().spawned(0.);
+        world
+            .spawn()
+            .insert(Player)
+            .insert(Weapon::new(0))
+            .insert(Armor {
+                hp,
+                shield: 0,
+                regen: None,
+            })
+            .insert(SpriteSize::from((20., 20.)))
+            .insert(Transform::default())
+            .id()
+    }
+
+    fn kills(world: &World) -> Vec<EnemyKind> {
+        let events = world.resource::<Events<EnemyKilled>>();
+        events
+            .get_reader()
+            .iter(events)
+            .map(|killed| killed.kind)
+            .collect()
+    }
+
+    #[test]
+    fn lost_ship_costs_a_weapon_level() {
+        let config = GameConfig::default();
+        let mut player_state = PlayerState::new(&config.player);
+        let lives = player_state.lives;
+        player_state.shot(1., 2);
+        assert_eq!(player_state.weapon_level, 1);
+        assert_eq!(player_state.lives, lives - 1);
+        player_state.shot(2., 0);
+        assert_eq!(player_state.weapon_level, 0);
+    }
+
+    #[test]
+    fn tank_takes_several_hits_and_flashes() {
+        let mut world = world();
+        let tank = spawn_enemy(&mut world, EnemyKind::Tank);
+        let hp = world.resource::<GameConfig>().enemy.tank.hp;
+        assert!(hp > 1);
+        let mut stage = SystemStage::single_threaded().with_system(player_laser_hit_enemy_system);
+
+        for left in (1..hp).rev() {
+            spawn_laser(&mut world);
+            stage.run(&mut world);
+            assert_eq!(world.get::<Health>(tank).unwrap().0, left);
+            assert!(world.get::<DamageFlash>(tank).is_some());
+            assert!(kills(&world).is_empty());
+        }
+
+        spawn_laser(&mut world);
+        stage.run(&mut world);
+        assert!(world.get_entity(tank).is_none());
+        assert_eq!(kills(&world), vec![EnemyKind::Tank]);
+        assert_eq!(world.resource::<EnemyCount>().0, 0);
+    }
+
+    #[test]
+    fn grunt_goes_down_in_one_hit() {
+        let mut world = world();
+        let grunt = spawn_enemy(&mut world, EnemyKind::Grunt);
+        spawn_laser(&mut world);
+        let mut stage = SystemStage::single_threaded().with_system(player_laser_hit_enemy_system);
+        stage.run(&mut world);
+        assert!(world.get_entity(grunt).is_none());
+        assert_eq!(kills(&world), vec![EnemyKind::Grunt]);
+    }
+
+    #[test]
+    fn ram_damages_both_ships() {
+        let mut world = world();
+        let player = spawn_player(&mut world, 3);
+        let tank = spawn_enemy(&mut world, EnemyKind::Tank);
+        let config = world.resource::<GameConfig>().clone();
+        let mut stage = SystemStage::single_threaded().with_system(enemy_ram_player_system);
+
+        stage.run(&mut world);
+        assert_eq!(
+            world.get::<Health>(tank).unwrap().0,
+            config.enemy.tank.hp - config.enemy.ram_damage
+        );
+        assert!(world.get::<DamageFlash>(tank).is_some());
+        assert_eq!(world.get::<Armor>(player).unwrap().hp, 2);
+        assert!(world.get::<DamageFlash>(player).is_some());
+
+        // the tank still in the way, no second ram right away
+        stage.run(&mut world);
+        assert_eq!(world.get::<Armor>(player).unwrap().hp, 2);
+        assert_eq!(
+            world.resource::<PlayerState>().invulnerable_until,
+            10. + config.player.ram_invulnerable_duration
+        );
+    }
+
+    #[test]
+    fn ram_on_the_last_hull_point_loses_both() {
+        let mut world = world();
+        let player = spawn_player(&mut world, 1);
+        let grunt = spawn_enemy(&mut world, EnemyKind::Grunt);
+        let lives = world.resource::<PlayerState>().lives;
+
+        SystemStage::single_threaded()
+            .with_system(enemy_ram_player_system)
+            .run(&mut world);
+
+        assert!(world.get_entity(grunt).is_none());
+        assert_eq!(kills(&world), vec![EnemyKind::Grunt]);
+        assert!(world.get_entity(player).is_none());
+        assert_eq!(world.resource::<PlayerState>().lives, lives - 1);
+    }
+}
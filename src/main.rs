@@ -1,300 +1,106 @@
-#![allow(unused)]
-use std::collections::HashSet;
-use std::f32::consts::PI;
+use bevy::{log::LogPlugin, prelude::*};
+use game_bevy::config::{asset_dir, GameConfig, CONFIG_FILE};
+use game_bevy::headless::{HeadlessConfig, HeadlessPlugin};
+use game_bevy::high_scores::HighScoreFile;
+use game_bevy::reload::SourceFile;
+use game_bevy::replay::{Replay, ReplayConfig, ReplayOutcome, ReplayPlugin};
+use game_bevy::rng::GameRng;
+use game_bevy::wave::script::{WaveScript, WAVES_FILE};
+use game_bevy::{GamePlugin, WindowedPlugin};
 
-use bevy::math::Vec3Swizzles;
-use bevy::{prelude::*, sprite::collide_aabb::collide};
-use components::{
-    Enemy, Explosion, ExplosionTimer, ExplosionToSpawn, FromEnemy, FromPlayer, Laser, Moveable,
-    Player, SpriteSize, Velocity,
-};
-use enemy::EnemyPlugin;
-use player::PlayerPlugin;
-
-mod components;
-mod player;
-mod enemy;
-
-// region: --- Assert Constants
-
-const PLAYER_SPRITE: &str = "player_a_01.png";
-const PLAYER_SIZE: (f32, f32) = (144., 75.);
-const PLAYER_LASER_SPRITE: &str = "laser_a_01.png";
-const PLAYER_LASER_SIZE: (f32, f32) = (9., 54.);
-
-const ENEMY_SPRITE: &str = "enemy_a_01.png";
-const ENEMY_SIZE: (f32, f32) = (144., 75.);
-const ENEMY_LASER_SPRITE: &str = "laser_b_01.png";
-const ENEMY_LASER_SIZE: (f32, f32) = (17., 55.);
-
-const EXPLOSION_SHEET: &str = "explo_a_sheet.png";
-const EXPLOSION_LEN: usize = 16;
-
-// endregion: --- Assert Constants
-
-// region: --- Resources
-
-// region: --- Game Constants
-
-const TIME_STEP: f32 = 1. / 60.;
-const BASE_SPEED: f32 = 500.;
-
-const PLAYER_RESPAWN_DELAY: f64 = 2.;
-const ENEMY_MAX: u32 = 4;
-const FORMATION_MEMBERS_MAX: u32 = 2;
-
-// endregion: --- Game Constants
-
-pub struct WinSize {
-    pub w: f32,
-    pub h: f32,
-}
-
-struct GameTextures {
-    player: Handle<Image>,
-    player_laser: Handle<Image>,
-    enemy: Handle<Image>,
-    enemy_laser: Handle<Image>,
-    explosion: Handle<TextureAtlas>,
-}
-
-struct EnemyCount(u32);
-
-struct PlayerState {
-    on: bool,
-    last_shot: f64,
-}
-
-impl Default for PlayerState {
-    fn default() -> Self {
-        Self {
-            on: false,
-            last_shot: -1.,
+fn main() {
+    // `--lint-waves <path>` checks a wave script and exits
+    if let Some(path) = WaveScript::lint_path_from_args(std::env::args()) {
+        match WaveScript::read(&path) {
+            Ok(script) => println!("{path:?}: {} waves, ok", script.waves.len()),
+            Err(err) => {
+                eprintln!("{err}");
+                std::process::exit(1);
+            }
         }
+        return;
     }
-}
-
-impl PlayerState {
-    pub fn shot(&mut self, time: f64) {
-        self.on = false;
-        self.last_shot = time;
-    }
-    pub fn spawned(&mut self) {
-        self.on = true;
-        self.last_shot = -1.;
-    }
-}
-
-// endregion: --- Resources
-const SPRITE_SCALE: f32 = 0.5;
-fn main() {
-    App::new()
-        .insert_resource(Color::rgb(0.04, 0.04, 0.04))
-        .insert_resource(WindowDescriptor {
-            title: "Rust Invaders!".to_string(),
-            height: 700.,
-            width: 600.,
-            ..Default::default()
-        })
-        .add_plugins(DefaultPlugins)
-        .add_plugin(PlayerPlugin)
-        .add_plugin(EnemyPlugin)
-        .add_startup_system(setup_system)
-        .add_system(moveable_system)
-        .add_system(player_laser_hit_enemy_system)
-        .add_system(enemy_laser_hit_player_system)
-        .add_system(explosion_to_spawn_system)
-        .add_system(explosion_animation_system)
-        .run();
-}
-
-fn setup_system(
-    mut commands: Commands,
-    assert_server: Res<AssetServer>,
-    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    mut windows: ResMut<Windows>,
-) {
-    // camera
-    commands.spawn_bundle(Camera2dBundle::default());
-
-    // add rectangle
-    // commands.spawn_bundle(SpriteBundle{
-    //     sprite: Sprite {
-    //         color: Color::rgb(0.25,0.25,0.75),
-    //         custom_size: Some(Vec2::new(150.,150.)),
-    //         ..Default::default()
-    //     },
-    //     ..Default::default()
-    // });
-
-    // capture window size
-    let window = windows.get_primary_mut().unwrap();
-    let (win_w, win_h) = (window.width(), window.height());
-
-    // position window
-    // window.set_position(IVec2::new(2780, 4900));
 
-    // add WinSize resource
-    let win_size = WinSize { w: win_w, h: win_h };
-    commands.insert_resource(win_size);
+    let mut app = App::new();
 
-    // create explosion texture atlas
-    let texture_handle = assert_server.load(EXPLOSION_SHEET);
-    let texture_atlas = TextureAtlas::from_grid(texture_handle, Vec2::new(64., 64.), 4, 4);
-    let explosion = texture_atlases.add(texture_atlas);
-
-    // add GameTextures resource
-    let game_textures = GameTextures {
-        player: assert_server.load(PLAYER_SPRITE),
-        player_laser: assert_server.load(PLAYER_LASER_SPRITE),
-        enemy: assert_server.load(ENEMY_SPRITE),
-        enemy_laser: assert_server.load(ENEMY_LASER_SPRITE),
-        explosion,
+    // tuning values, `--config <path>` or `assets/game.config.ron`
+    let config = match GameConfig::path_from_args(std::env::args()) {
+        // named on the command line, so it has to be there
+        Some(path) => GameConfig::read(&path).map(|config| (config, path)),
+        None => {
+            let path = asset_dir().join(CONFIG_FILE);
+            GameConfig::load(&path).map(|config| (config, path))
+        }
     };
-    commands.insert_resource(game_textures);
-    commands.insert_resource(EnemyCount(0));
-}
-
-fn moveable_system(
-    mut commands: Commands,
-    win_size: Res<WinSize>,
-    mut query: Query<(Entity, &Velocity, &mut Transform, &Moveable)>,
-) {
-    for (entity, velocity, mut transform, moveable) in query.iter_mut() {
-        let translation = &mut transform.translation;
-        translation.x += velocity.x * TIME_STEP * BASE_SPEED;
-        translation.y += velocity.y * TIME_STEP * BASE_SPEED;
-
-        if moveable.auto_despawn {
-            const MARGIN: f32 = 200.;
-            if translation.y > win_size.h / 2. + MARGIN
-                || translation.y < -win_size.h / 2. - MARGIN
-                || translation.x > win_size.w / 2. + MARGIN
-                || translation.x < -win_size.w / 2. - MARGIN
-            {
-                // println!("---> despawn {entity:?}");
-                commands.entity(entity).despawn();
-            }
+    match config {
+        Ok((config, config_path)) => {
+            app.insert_resource(config)
+                .insert_resource(SourceFile::<GameConfig>::new(config_path));
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(1);
         }
     }
-}
 
-fn player_laser_hit_enemy_system(
-    mut commands: Commands,
-    mut enemy_count: ResMut<EnemyCount>,
-    laser_query: Query<(Entity, &Transform, &SpriteSize), (With<Laser>, With<FromPlayer>)>,
-    enemy_query: Query<(Entity, &Transform, &SpriteSize), (With<Enemy>)>,
-) {
-    let mut despawned_entities: HashSet<Entity> = HashSet::new();
-    for (laser_entity, laser_tf, laser_size) in laser_query.iter() {
-        if despawned_entities.contains(&laser_entity) {
-            continue;
+    // enemy waves, `--waves <path>` or `assets/game.waves.ron`
+    let script = match WaveScript::path_from_args(std::env::args()) {
+        // named on the command line, so it has to be there
+        Some(path) => WaveScript::read(&path).map(|script| (script, path)),
+        None => {
+            let path = asset_dir().join(WAVES_FILE);
+            WaveScript::load(&path).map(|script| (script, path))
         }
-
-        let laser_scale = Vec2::from(laser_tf.scale.xy());
-
-        for (enemy_entity, enemy_tf, enemy_size) in enemy_query.iter() {
-            if despawned_entities.contains(&enemy_entity)
-                || despawned_entities.contains(&laser_entity)
-            {
-                continue;
-            }
-
-            let enemy_scale = Vec2::from(enemy_tf.scale.xy());
-
-            let collision = collide(
-                laser_tf.translation,
-                laser_size.0 * laser_scale,
-                enemy_tf.translation,
-                enemy_size.0 * enemy_scale,
-            );
-
-            if let Some(_) = collision {
-                // remove enemy
-                commands.entity(enemy_entity).despawn();
-                despawned_entities.insert(enemy_entity);
-                enemy_count.0 -= 1;
-                // remove laser
-                commands.entity(laser_entity).despawn();
-                despawned_entities.insert(laser_entity);
-                // spawn the explosion
-                commands
-                    .spawn()
-                    .insert(ExplosionToSpawn(enemy_tf.translation.clone()));
-            }
+    };
+    match script {
+        Ok((script, waves_path)) => {
+            app.insert_resource(script)
+                .insert_resource(SourceFile::<WaveScript>::new(waves_path));
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(1);
         }
     }
-}
 
-fn enemy_laser_hit_player_system(
-    mut commands: Commands,
-    mut player_state: ResMut<PlayerState>,
-    time: Res<Time>,
-    laser_query: Query<(Entity, &Transform, &SpriteSize), (With<Laser>, With<FromEnemy>)>,
-    player_query: Query<(Entity, &Transform, &SpriteSize), (With<Player>)>,
-) {
-    if let Ok((player_entitiy, player_tf, player_size)) = player_query.get_single() {
-        let player_scale = Vec2::from(player_tf.scale.xy());
-
-        for (laser_entity, laser_tf, laser_size) in laser_query.iter() {
-            let laser_scale = Vec2::from(laser_tf.scale.xy());
+    // `--headless` runs the game loop without window, renderer or assets
+    match HeadlessConfig::from_args(std::env::args()) {
+        // `DefaultPlugins` bring the logger along with the window
+        Some(config) => app.add_plugin(HeadlessPlugin(config)).add_plugin(LogPlugin),
+        None => app.add_plugin(WindowedPlugin),
+    };
 
-            let collision = collide(
-                player_tf.translation,
-                player_size.0 * player_scale,
-                laser_tf.translation,
-                laser_size.0 * laser_scale,
-            );
+    if let Some(path) = HighScoreFile::path_from_args(std::env::args()) {
+        app.insert_resource(HighScoreFile(Some(path)));
+    }
 
-            if let Some(_) = collision {
-                commands.entity(player_entitiy).despawn();
-                player_state.shot(time.seconds_since_startup());
+    if let Some(seed) = GameRng::seed_from_args(std::env::args()) {
+        app.insert_resource(GameRng::with_seed(seed));
+    }
 
-                commands.entity(laser_entity).despawn();
-                commands
-                    .spawn()
-                    .insert(ExplosionToSpawn(player_tf.translation.clone()));
-                break;
-            }
+    // `--record <path>` saves the inputs, `--replay <path>` feeds them back
+    let outcome = ReplayOutcome::default();
+    match ReplayConfig::from_args(std::env::args()) {
+        Some(ReplayConfig::Record(path)) => {
+            app.add_plugin(ReplayPlugin::Record(path));
         }
+        Some(ReplayConfig::Playback(path)) => match Replay::load(&path) {
+            Ok(replay) => {
+                app.insert_resource(outcome.clone())
+                    .add_plugin(ReplayPlugin::Playback(replay));
+            }
+            Err(err) => {
+                eprintln!("failed to load replay {path:?}: {err}");
+                std::process::exit(1);
+            }
+        },
+        None => {}
     }
-}
-
-
-fn explosion_to_spawn_system(
-    mut commands: Commands,
-    game_textures: Res<GameTextures>,
-    query: Query<(Entity, &ExplosionToSpawn)>,
-) {
-    for (explosion_to_spawn_entity, explosion_to_spawn) in query.iter() {
-        commands
-            .spawn_bundle(SpriteSheetBundle {
-                texture_atlas: game_textures.explosion.clone(),
-                transform: Transform {
-                    translation: explosion_to_spawn.0,
-                    ..Default::default()
-                },
-                ..Default::default()
-            })
-            .insert(Explosion)
-            .insert(ExplosionTimer::default());
 
-        commands.entity(explosion_to_spawn_entity).despawn();
-    }
-}
+    app.add_plugin(GamePlugin).run();
 
-fn explosion_animation_system(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut query: Query<(Entity, &mut ExplosionTimer, &mut TextureAtlasSprite), With<Explosion>>,
-) {
-    for (entity, mut timer, mut sprite) in query.iter_mut() {
-        timer.0.tick(time.delta());
-        if timer.0.finished() {
-            sprite.index += 1;
-            if sprite.index >= EXPLOSION_LEN {
-                commands.entity(entity).despawn();
-            }
-        }
+    // a replay that did not end the way it was recorded
+    if outcome.mismatched() {
+        std::process::exit(1);
     }
 }
@@ -0,0 +1,366 @@
+use std::f32::consts::PI;
+
+use bevy::{math::Vec3Swizzles, prelude::*};
+use serde::Deserialize;
+
+use crate::{
+    components::{FromEnemy, Laser, Moveable, Player, SpriteSize, Velocity},
+    config::GameConfig,
+    moveable_system,
+    rng::RngSystem,
+    state::GameState,
+    GameSystem, GameTextures,
+};
+
+/// seconds a bullet lives, so curving ones do not loop on screen forever
+const BULLET_LIFETIME: f32 = 8.;
+
+// region: --- Bullet Pattern
+
+/// Shape of a volley of enemy lasers and how they fly.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct BulletPattern {
+    pub aim: Aim,
+    /// lasers per volley
+    pub ways: u32,
+    /// radians between the first and the last laser of a volley
+    pub spread: f32,
+    /// volleys each time the emitter is triggered
+    pub burst: u32,
+    /// seconds between two volleys of a burst
+    pub burst_interval: f32,
+    /// as a `Velocity` length
+    pub speed: f32,
+    /// change of speed per second
+    pub acceleration: f32,
+    /// radians per second the lasers turn while flying
+    pub curve: f32,
+}
+
+/// Direction a volley is centered on.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum Aim {
+    Down,
+    /// at the player's position when the volley leaves
+    Player,
+    /// starting straight down, turning `turn` radians per second
+    Spiral {
+        turn: f32,
+    },
+}
+
+impl Default for BulletPattern {
+    /// One laser straight down.
+    fn default() -> Self {
+        Self {
+            aim: Aim::Down,
+            ways: 1,
+            spread: 0.,
+            burst: 1,
+            burst_interval: 0.1,
+            speed: 1.,
+            acceleration: 0.,
+            curve: 0.,
+        }
+    }
+}
+
+impl BulletPattern {
+    /// One message per invalid field, prefixed with `name`.
+    pub fn validate(&self, name: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.ways == 0 {
+            errors.push(format!("{name}.ways must be at least 1"));
+        }
+        if self.spread < 0. {
+            errors.push(format!("{name}.spread must not be negative"));
+        }
+        if self.burst == 0 {
+            errors.push(format!("{name}.burst must be at least 1"));
+        }
+        if self.burst_interval <= 0. {
+            errors.push(format!("{name}.burst_interval must be greater than 0"));
+        }
+        if self.speed <= 0. {
+            errors.push(format!("{name}.speed must be greater than 0"));
+        }
+        errors
+    }
+
+    /// Direction of a volley leaving `position`, radians from the x axis.
+    /// `clock` is the age of the emitter, turning spirals.
+    fn center(&self, position: Vec2, player: Option<Vec2>, clock: f32) -> f32 {
+        let down = -PI / 2.;
+        match self.aim {
+            Aim::Down => down,
+            Aim::Player => match player {
+                Some(target) if target != position => {
+                    let direction = target - position;
+                    direction.y.atan2(direction.x)
+                }
+                // nobody to aim at, fire straight down
+                _ => down,
+            },
+            Aim::Spiral { turn } => down + turn * clock,
+        }
+    }
+
+    /// Angle of each laser of a volley, fanned out around `center`.
+    fn angles(&self, center: f32) -> Vec<f32> {
+        if self.ways <= 1 {
+            return vec![center; self.ways as usize];
+        }
+        let step = self.spread / (self.ways - 1) as f32;
+        let first = center - self.spread / 2.;
+        (0..self.ways).map(|i| first + step * i as f32).collect()
+    }
+}
+
+// endregion: --- Bullet Pattern
+
+// region: --- Pattern Components
+
+/// Fires a `BulletPattern` each time it is triggered.
+#[derive(Component)]
+pub struct Emitter {
+    pub pattern: BulletPattern,
+    /// from the entity position to where the lasers leave
+    offset: Vec2,
+    /// seconds since the emitter was made, drives spirals
+    clock: f32,
+    /// volleys left in the current burst
+    volleys_left: u32,
+    /// seconds until the next volley of the burst
+    next_volley: f32,
+}
+
+impl Emitter {
+    pub fn new(pattern: BulletPattern, offset: Vec2) -> Self {
+        Self {
+            pattern,
+            offset,
+            clock: 0.,
+            volleys_left: 0,
+            next_volley: 0.,
+        }
+    }
+
+    /// Starts a burst, unless one is still going.
+    pub fn trigger(&mut self) {
+        if self.volleys_left == 0 {
+            self.volleys_left = self.pattern.burst;
+            self.next_volley = 0.;
+        }
+    }
+}
+
+/// Laser that speeds up, slows down or turns while flying.
+#[derive(Component)]
+pub struct Bullet {
+    /// radians, 0 is to the right
+    angle: f32,
+    speed: f32,
+    acceleration: f32,
+    curve: f32,
+    age: f32,
+}
+
+// endregion: --- Pattern Components
+
+pub struct PatternPlugin;
+
+impl Plugin for PatternPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(GameState::Playing)
+                // once the enemies and bosses have decided to fire
+                .with_system(
+                    emitter_system
+                        .label(GameSystem::Spawn)
+                        .after(GameSystem::Input)
+                        .after(RngSystem::EnemyFire),
+                )
+                // curves the bullets before they move
+                .with_system(
+                    bullet_system
+                        .label(GameSystem::Movement)
+                        .after(GameSystem::Spawn)
+                        .before(moveable_system),
+                ),
+        );
+    }
+}
+
+/// Enemy laser leaving `position` at `angle`, flying as `pattern` says.
+fn spawn_bullet(
+    commands: &mut Commands,
+    config: &GameConfig,
+    game_textures: &GameTextures,
+    position: Vec3,
+    angle: f32,
+    pattern: &BulletPattern,
+) {
+    let scale = config.sprite_scale;
+    let velocity = Vec2::new(angle.cos(), angle.sin()) * pattern.speed;
+    commands
+        .spawn_bundle(SpriteBundle {
+            texture: game_textures.enemy_laser.clone(),
+            transform: Transform {
+                translation: position,
+                // the sprite points up
+                rotation: Quat::from_rotation_z(angle - PI / 2.),
+                scale: Vec3::new(scale, scale, 1.),
+            },
+            ..Default::default()
+        })
+        .insert(Laser)
+        .insert(SpriteSize::from(config.enemy.laser_size))
+        .insert(FromEnemy)
+        .insert(Moveable { auto_despawn: true })
+        .insert(Velocity {
+            x: velocity.x,
+            y: velocity.y,
+        })
+        .insert(Bullet {
+            angle,
+            speed: pattern.speed,
+            acceleration: pattern.acceleration,
+            curve: pattern.curve,
+            age: 0.,
+        });
+}
+
+fn emitter_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    game_textures: Res<GameTextures>,
+    mut emitter_query: Query<(&mut Emitter, &Transform)>,
+    player_query: Query<&Transform, (With<Player>, Without<Emitter>)>,
+) {
+    let delta = time.delta_seconds();
+    let player_position = player_query.get_single().ok().map(|tf| tf.translation.xy());
+
+    for (mut emitter, tf) in emitter_query.iter_mut() {
+        emitter.clock += delta;
+        if emitter.volleys_left == 0 {
+            continue;
+        }
+        emitter.next_volley -= delta;
+        if emitter.next_volley > 0. {
+            continue;
+        }
+        emitter.volleys_left -= 1;
+        emitter.next_volley += emitter.pattern.burst_interval;
+
+        let pattern = &emitter.pattern;
+        let position = tf.translation.xy() + emitter.offset;
+        let center = pattern.center(position, player_position, emitter.clock);
+        for angle in pattern.angles(center) {
+            let translation = position.extend(0.);
+            spawn_bullet(
+                &mut commands,
+                &config,
+                &game_textures,
+                translation,
+                angle,
+                pattern,
+            );
+        }
+    }
+}
+
+fn bullet_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Bullet, &mut Velocity, &mut Transform)>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut bullet, mut velocity, mut tf) in query.iter_mut() {
+        bullet.age += delta;
+        if bullet.age > BULLET_LIFETIME {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if bullet.acceleration == 0. && bullet.curve == 0. {
+            continue;
+        }
+
+        bullet.speed = (bullet.speed + bullet.acceleration * delta).max(0.);
+        bullet.angle += bullet.curve * delta;
+        let direction = Vec2::new(bullet.angle.cos(), bullet.angle.sin());
+        (velocity.x, velocity.y) = (direction.x * bullet.speed, direction.y * bullet.speed);
+        tf.rotation = Quat::from_rotation_z(bullet.angle - PI / 2.);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOWN: f32 = -PI / 2.;
+
+    fn assert_angles(actual: Vec<f32>, expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len(), "{actual:?}");
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-5, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn fan_spreads_evenly_around_the_center() {
+        let pattern = BulletPattern {
+            ways: 5,
+            spread: 1.,
+            ..Default::default()
+        };
+        assert_angles(
+            pattern.angles(DOWN),
+            &[DOWN - 0.5, DOWN - 0.25, DOWN, DOWN + 0.25, DOWN + 0.5],
+        );
+    }
+
+    #[test]
+    fn single_way_ignores_the_spread() {
+        let pattern = BulletPattern {
+            spread: 1.,
+            ..Default::default()
+        };
+        assert_angles(pattern.angles(DOWN), &[DOWN]);
+    }
+
+    #[test]
+    fn aimed_at_the_player() {
+        let pattern = BulletPattern {
+            aim: Aim::Player,
+            ..Default::default()
+        };
+        let position = Vec2::new(10., 10.);
+        let right = pattern.center(position, Some(Vec2::new(50., 10.)), 0.);
+        assert!(right.abs() < 1e-5);
+        let below_left = pattern.center(position, Some(Vec2::new(0., 0.)), 0.);
+        assert!((below_left + 3. * PI / 4.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn aimed_without_a_target_fires_down() {
+        let pattern = BulletPattern {
+            aim: Aim::Player,
+            ..Default::default()
+        };
+        let position = Vec2::new(10., 10.);
+        assert_eq!(pattern.center(position, None, 0.), DOWN);
+        assert_eq!(pattern.center(position, Some(position), 0.), DOWN);
+    }
+
+    #[test]
+    fn spiral_turns_with_the_emitter_clock() {
+        let pattern = BulletPattern {
+            aim: Aim::Spiral { turn: 2. },
+            ..Default::default()
+        };
+        assert_eq!(pattern.center(Vec2::ZERO, None, 0.), DOWN);
+        assert!((pattern.center(Vec2::ZERO, None, 0.5) - (DOWN + 1.)).abs() < 1e-5);
+    }
+}
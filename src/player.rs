@@ -1,50 +1,93 @@
-use bevy::prelude::*;
-use bevy::time::FixedTimestep;
+use std::f32::consts::{PI, TAU};
+
+use bevy::{math::Vec3Swizzles, prelude::*};
 
 use crate::{
-    components::{FromPlayer, Laser, Moveable, Player, SpriteSize, Velocity},
-    GameTextures, PlayerState, WinSize, PLAYER_LASER_SIZE, PLAYER_RESPAWN_DELAY, PLAYER_SIZE,
-    SPRITE_SCALE,
+    components::{
+        Armor, Damage, DamageFlash, Enemy, FromPlayer, Health, Homing, Laser, Moveable, Piercing,
+        Player, SpriteSize, Velocity, Weapon,
+    },
+    config::GameConfig,
+    moveable_system,
+    state::GameState,
+    GameClock, GameSystem, GameTextures, PlayerState, WinSize,
 };
 
+/// shield sprite size, on the ship size
+const SHIELD_SCALE: f32 = 1.3;
+
+/// Shield sprite around the ship, a child of the `Player`.
+#[derive(Component)]
+struct PlayerShield;
+
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         // app.add_startup_system_to_stage(StartupStage::PostStartup, player_spawn_system)
-        app.insert_resource(PlayerState::default())
-            .add_system_set(
-                SystemSet::new()
-                    .with_run_criteria(FixedTimestep::step(0.5))
-                    .with_system(player_spawn_system),
-            )
-            .add_system(player_fire_system)
-            .add_system(player_keyboard_event_system);
+        app.insert_resource(PlayerState::default()).add_system_set(
+            SystemSet::on_update(GameState::Playing)
+                .with_system(player_keyboard_event_system.label(GameSystem::Input))
+                .with_system(player_fire_system.label(GameSystem::Input))
+                .with_system(
+                    player_spawn_system
+                        .label(GameSystem::Spawn)
+                        .after(GameSystem::Input),
+                )
+                // steers the lasers before they move
+                .with_system(
+                    homing_system
+                        .label(GameSystem::Movement)
+                        .after(GameSystem::Spawn)
+                        .before(moveable_system),
+                )
+                .with_system(
+                    player_invulnerable_blink_system
+                        .label(GameSystem::Cleanup)
+                        .after(GameSystem::Collision),
+                )
+                .with_system(
+                    player_shield_system
+                        .label(GameSystem::Cleanup)
+                        .after(GameSystem::Collision),
+                )
+                .with_system(
+                    player_damage_flash_system
+                        .label(GameSystem::Cleanup)
+                        .after(GameSystem::Collision),
+                ),
+        );
     }
 }
 
 fn player_spawn_system(
     mut commands: Commands,
     mut player_state: ResMut<PlayerState>,
-    time: Res<Time>,
+    mut state: ResMut<State<GameState>>,
+    clock: Res<GameClock>,
+    config: Res<GameConfig>,
     game_textures: Res<GameTextures>,
     win_size: Res<WinSize>,
 ) {
-    let now = time.seconds_since_startup();
+    let now = clock.0;
     let last_shot = player_state.last_shot;
+    let (player_size, scale) = (config.player.size, config.sprite_scale);
+
+    if !player_state.on && (last_shot == -1. || now > last_shot + config.player.respawn_delay) {
+        // out of lives, let the last explosion play out then end the game
+        if player_state.lives == 0 {
+            let _ = state.set(GameState::GameOver);
+            return;
+        }
 
-    if !player_state.on && (last_shot == -1. || now > last_shot + PLAYER_RESPAWN_DELAY) {
         let bottom = -win_size.h / 2.;
+        let armor = config.player.armor();
         commands
             .spawn_bundle(SpriteBundle {
                 texture: game_textures.player.clone(),
                 transform: Transform {
-                    translation: Vec3::new(
-                        0.,
-                        bottom + PLAYER_SIZE.1 / 2. * SPRITE_SCALE + 5.,
-                        10.,
-                    ),
-                    scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
+                    translation: Vec3::new(0., bottom + player_size.1 / 2. * scale + 5., 10.),
+                    scale: Vec3::new(scale, scale, 1.),
                     ..Default::default()
                 },
                 ..Default::default()
@@ -54,45 +97,194 @@ fn player_spawn_system(
             .insert(Moveable {
                 auto_despawn: false,
             })
-            .insert(SpriteSize::from(PLAYER_SIZE));
+            .insert(SpriteSize::from(player_size))
+            .insert(Weapon::new(player_state.weapon_level))
+            .insert(Armor {
+                hp: armor.hp,
+                shield: armor.shield_start,
+                regen: (armor.shield_regen > 0.)
+                    .then(|| Timer::from_seconds(armor.shield_regen, true)),
+            })
+            .with_children(|parent| {
+                // hidden by `player_shield_system` while the shield is down
+                let (w, h) = player_size;
+                parent
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::rgba(0.3, 0.8, 1., 0.4),
+                            custom_size: Some(Vec2::new(w, h) * SHIELD_SCALE),
+                            ..Default::default()
+                        },
+                        transform: Transform::from_xyz(0., 0., 1.),
+                        ..Default::default()
+                    })
+                    .insert(PlayerShield);
+            });
 
-        player_state.spawned();
+        player_state.spawned(now);
     }
 }
+fn player_invulnerable_blink_system(
+    clock: Res<GameClock>,
+    player_state: Res<PlayerState>,
+    config: Res<GameConfig>,
+    mut query: Query<&mut Visibility, With<Player>>,
+) {
+    if let Ok(mut visibility) = query.get_single_mut() {
+        let now = clock.0;
+        visibility.is_visible = !player_state.invulnerable(now, &config.player)
+            || ((now - player_state.last_spawn) * 10.) as i64 % 2 == 0;
+    }
+}
+
+fn player_shield_system(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    mut player_query: Query<&mut Armor, With<Player>>,
+    mut shield_query: Query<(&mut Visibility, &mut Sprite), With<PlayerShield>>,
+) {
+    let max = config.player.armor().shield;
+    if let Ok(mut armor) = player_query.get_single_mut() {
+        if armor.shield < max {
+            let regained = armor
+                .regen
+                .as_mut()
+                .is_some_and(|regen| regen.tick(time.delta()).just_finished());
+            if regained {
+                armor.shield += 1;
+            }
+        }
+
+        // fades as it weakens
+        let alpha = 0.2 + 0.4 * armor.shield as f32 / max.max(1) as f32;
+        for (mut visibility, mut sprite) in shield_query.iter_mut() {
+            visibility.is_visible = armor.shield > 0;
+            sprite.color.set_a(alpha);
+        }
+    }
+}
+
+/// Tints the ship red after a hit on the hull.
+fn player_damage_flash_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut DamageFlash, &mut Sprite), With<Player>>,
+) {
+    for (entity, mut flash, mut sprite) in query.iter_mut() {
+        if flash.0.tick(time.delta()).finished() {
+            sprite.color = Color::WHITE;
+            commands.entity(entity).remove::<DamageFlash>();
+        } else {
+            sprite.color = Color::rgb(1., 0.2, 0.2);
+        }
+    }
+}
+
 fn player_fire_system(
     mut commands: Commands,
+    time: Res<Time>,
     kb: Res<Input<KeyCode>>,
+    config: Res<GameConfig>,
     game_textures: Res<GameTextures>,
-    query: Query<&Transform, With<Player>>,
+    mut query: Query<(&Transform, &mut Weapon), With<Player>>,
 ) {
-    if let Ok(player_if) = query.get_single() {
-        if kb.just_pressed(KeyCode::Space) {
+    if let Ok((player_if, mut weapon)) = query.get_single_mut() {
+        weapon.cooldown.tick(time.delta());
+        if let Some(heat) = &config.player.heat {
+            weapon.cool(heat, time.delta_seconds());
+        }
+
+        // held down, fires at the weapon rate
+        if kb.pressed(KeyCode::Space) && weapon.cooldown.finished() && !weapon.overheated {
             let (x, y) = (player_if.translation.x, player_if.translation.y);
-            let x_offset = PLAYER_SIZE.0 / 2. * SPRITE_SCALE - 5.;
+            let scale = config.sprite_scale;
+            // the config may have lost levels on a reload
+            let level = weapon.level.min(config.player.weapons.len() - 1);
+            let weapon_config = &config.player.weapons[level];
+            let texture = game_textures
+                .weapons
+                .get(level)
+                .cloned()
+                .unwrap_or_default();
+            weapon.cooldown = Timer::from_seconds(weapon_config.cooldown, false);
 
-            let mut spawn_laser = |x_offset: f32| {
-                commands
-                    .spawn_bundle(SpriteBundle {
-                        texture: game_textures.player_laser.clone(),
-                        transform: Transform {
-                            translation: Vec3::new(x + x_offset, y + 15., 0.),
-                            scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
-                            ..Default::default()
-                        },
-                        ..Default::default()
+            for shot in weapon_config.shots.iter() {
+                let (x_offset, y_offset) = shot.offset;
+                let direction = Vec2::new(-shot.angle.sin(), shot.angle.cos());
+                let mut laser = commands.spawn_bundle(SpriteBundle {
+                    texture: texture.clone(),
+                    transform: Transform {
+                        translation: Vec3::new(x + x_offset, y + y_offset, 0.),
+                        rotation: Quat::from_rotation_z(shot.angle),
+                        scale: Vec3::new(scale, scale, 1.),
+                    },
+                    ..Default::default()
+                });
+                laser
+                    .insert(Velocity {
+                        x: direction.x * weapon_config.speed,
+                        y: direction.y * weapon_config.speed,
                     })
-                    .insert(Velocity { x: 0., y: 1. })
                     .insert(Moveable { auto_despawn: true })
                     .insert(FromPlayer)
                     .insert(Laser)
-                    .insert(SpriteSize::from(PLAYER_LASER_SIZE));
-            };
-            spawn_laser(x_offset);
-            spawn_laser(-x_offset);
+                    .insert(Damage(weapon_config.damage))
+                    .insert(SpriteSize::from(weapon_config.size));
+                if weapon_config.piercing {
+                    laser.insert(Piercing::default());
+                }
+                if weapon_config.homing > 0. {
+                    laser.insert(Homing {
+                        turn: weapon_config.homing,
+                    });
+                }
+            }
+
+            if let Some(heat) = &config.player.heat {
+                weapon.heat_up(heat);
+            }
         }
     }
 }
 
+/// Turns homing projectiles toward the nearest enemy that can be hit.
+fn homing_system(
+    time: Res<Time>,
+    mut laser_query: Query<(&Homing, &mut Velocity, &mut Transform), Without<Enemy>>,
+    enemy_query: Query<&Transform, (With<Enemy>, With<Health>)>,
+) {
+    let delta = time.delta_seconds();
+    for (homing, mut velocity, mut tf) in laser_query.iter_mut() {
+        let position = tf.translation.xy();
+        let target = enemy_query
+            .iter()
+            .map(|enemy_tf| enemy_tf.translation.xy())
+            .min_by(|a, b| {
+                let (a, b) = (a.distance_squared(position), b.distance_squared(position));
+                a.total_cmp(&b)
+            });
+        let target = match target {
+            Some(target) => target,
+            None => continue,
+        };
+
+        // the shortest way round, no faster than the turn rate
+        let (speed, angle) = (
+            Vec2::new(velocity.x, velocity.y).length(),
+            velocity.y.atan2(velocity.x),
+        );
+        let to_target = target - position;
+        let wanted = to_target.y.atan2(to_target.x);
+        let turn = (wanted - angle + PI).rem_euclid(TAU) - PI;
+        let max_turn = homing.turn * delta;
+        let angle = angle + turn.clamp(-max_turn, max_turn);
+
+        (velocity.x, velocity.y) = (angle.cos() * speed, angle.sin() * speed);
+        // the sprite points up
+        tf.rotation = Quat::from_rotation_z(angle - PI / 2.);
+    }
+}
+
 fn player_keyboard_event_system(
     kb: Res<Input<KeyCode>>,
     mut query: Query<&mut Velocity, With<Player>>,
@@ -0,0 +1,191 @@
+use bevy::{math::Vec3Swizzles, prelude::*, sprite::collide_aabb::collide};
+use rand::Rng;
+
+use crate::{
+    components::{Armor, Moveable, Player, SpriteSize, Velocity, Weapon},
+    config::{DropConfig, GameConfig},
+    rng::{GameRng, RngSystem},
+    score::{EnemyKilled, Score},
+    state::GameState,
+    GameSystem, PlayerState,
+};
+
+// region: --- Power-Up Components
+
+/// Power-up drifting down, picked up by flying into it.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerUp {
+    /// one weapon level up
+    Weapon,
+    /// refills the shield
+    Shield,
+    ExtraLife,
+    /// one more smart bomb in stock
+    Bomb,
+    /// `power_ups.score_bonus` points
+    Score,
+}
+
+impl PowerUp {
+    fn color(&self) -> Color {
+        match self {
+            PowerUp::Weapon => Color::rgb(1., 0.6, 0.1),
+            PowerUp::Shield => Color::rgb(0.3, 0.8, 1.),
+            PowerUp::ExtraLife => Color::rgb(0.3, 1., 0.4),
+            PowerUp::Bomb => Color::rgb(1., 0.2, 0.2),
+            PowerUp::Score => Color::rgb(1., 0.9, 0.2),
+        }
+    }
+
+    /// Power-up `roll` (from 0 to 1) lands on with the `drops` chances, if any.
+    fn roll(drops: &DropConfig, roll: f64) -> Option<PowerUp> {
+        let chances = [
+            (PowerUp::Weapon, drops.weapon),
+            (PowerUp::Shield, drops.shield),
+            (PowerUp::ExtraLife, drops.extra_life),
+            (PowerUp::Bomb, drops.bomb),
+            (PowerUp::Score, drops.score),
+        ];
+        let mut total = 0.;
+        for (power_up, chance) in chances {
+            total += chance;
+            if roll < total {
+                return Some(power_up);
+            }
+        }
+        None
+    }
+}
+
+// endregion: --- Power-Up Components
+
+/// Sent for every power-up the player picks up.
+pub struct PowerUpCollected(pub PowerUp);
+
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PowerUpCollected>().add_system_set(
+            SystemSet::on_update(GameState::Playing)
+                .with_system(
+                    power_up_drop_system
+                        .label(RngSystem::PowerUpDrop)
+                        .label(GameSystem::Collision)
+                        .after(RngSystem::EnemyFire),
+                )
+                // last of the hits, the player may have been shot this frame
+                .with_system(
+                    power_up_collect_system
+                        .label(GameSystem::Collision)
+                        .after(RngSystem::PowerUpDrop),
+                ),
+        );
+    }
+}
+
+fn power_up_drop_system(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    mut events: EventReader<EnemyKilled>,
+) {
+    let (w, h) = config.power_ups.size;
+    for event in events.iter() {
+        let drops = &config.enemy.kind(event.kind).drops;
+        let power_up = match PowerUp::roll(drops, rng.gen()) {
+            Some(power_up) => power_up,
+            None => continue,
+        };
+
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: power_up.color(),
+                    custom_size: Some(Vec2::new(w, h)),
+                    ..Default::default()
+                },
+                transform: Transform {
+                    translation: Vec3::new(event.position.x, event.position.y, 5.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(power_up)
+            .insert(SpriteSize::from(config.power_ups.size))
+            .insert(Velocity {
+                x: 0.,
+                y: -config.power_ups.speed,
+            })
+            .insert(Moveable { auto_despawn: true });
+    }
+}
+
+fn power_up_collect_system(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    mut player_state: ResMut<PlayerState>,
+    mut score: ResMut<Score>,
+    mut collected: EventWriter<PowerUpCollected>,
+    power_up_query: Query<(Entity, &Transform, &SpriteSize, &PowerUp)>,
+    mut player_query: Query<(&Transform, &SpriteSize, &mut Weapon, &mut Armor), With<Player>>,
+) {
+    let (player_tf, player_size, mut weapon, mut armor) = match player_query.get_single_mut() {
+        Ok(player) => player,
+        Err(_) => return,
+    };
+
+    for (entity, tf, size, power_up) in power_up_query.iter() {
+        let collision = collide(
+            player_tf.translation,
+            player_size.0 * player_tf.scale.xy(),
+            tf.translation,
+            size.0 * tf.scale.xy(),
+        );
+        if collision.is_none() {
+            continue;
+        }
+
+        commands.entity(entity).despawn();
+        match power_up {
+            PowerUp::Weapon => weapon.upgrade(config.player.weapons.len() - 1),
+            // back to full, no effect in classic mode
+            PowerUp::Shield => armor.shield = config.player.armor().shield,
+            PowerUp::ExtraLife => player_state.lives += 1,
+            PowerUp::Bomb => {
+                player_state.bombs = (player_state.bombs + 1).min(config.player.bomb.max);
+            }
+            PowerUp::Score => score.bonus(config.power_ups.score_bonus),
+        }
+        collected.send(PowerUpCollected(*power_up));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roll_lands_on_the_chances_in_order() {
+        let drops = DropConfig {
+            weapon: 0.1,
+            shield: 0.2,
+            extra_life: 0.,
+            bomb: 0.1,
+            score: 0.1,
+        };
+        assert_eq!(PowerUp::roll(&drops, 0.), Some(PowerUp::Weapon));
+        assert_eq!(PowerUp::roll(&drops, 0.05), Some(PowerUp::Weapon));
+        // the upper bound belongs to the next power-up
+        assert_eq!(PowerUp::roll(&drops, 0.1), Some(PowerUp::Shield));
+        // zero chance is skipped
+        assert_eq!(PowerUp::roll(&drops, 0.35), Some(PowerUp::Bomb));
+        assert_eq!(PowerUp::roll(&drops, 0.45), Some(PowerUp::Score));
+        assert_eq!(PowerUp::roll(&drops, 0.6), None);
+    }
+
+    #[test]
+    fn no_chances_never_drop() {
+        assert_eq!(PowerUp::roll(&DropConfig::default(), 0.), None);
+    }
+}
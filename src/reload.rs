@@ -0,0 +1,196 @@
+use std::{
+    fs,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    asset::{Asset, AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    utils::BoxedFuture,
+};
+
+use crate::config::{asset_dir, ConfigError, GameConfig};
+use crate::wave::script::WaveScript;
+
+/// Resource read from a RON file at startup, that `ReloadPlugin` keeps up to
+/// date with the file.
+pub trait RonAsset: Asset + Clone {
+    /// file name endings picked up by the loader, like `config.ron`
+    const EXTENSIONS: &'static [&'static str];
+
+    /// Parses and validates the file content.
+    fn from_ron(content: &str) -> Result<Self, ConfigError>;
+}
+
+impl RonAsset for GameConfig {
+    const EXTENSIONS: &'static [&'static str] = &["config.ron"];
+
+    fn from_ron(content: &str) -> Result<Self, ConfigError> {
+        GameConfig::from_ron(content)
+    }
+}
+
+impl RonAsset for WaveScript {
+    const EXTENSIONS: &'static [&'static str] = &["waves.ron"];
+
+    fn from_ron(content: &str) -> Result<Self, ConfigError> {
+        WaveScript::from_ron(content)
+    }
+}
+
+/// File a `T` resource was read from, given on the command line or the asset
+/// folder one.
+pub struct SourceFile<T> {
+    pub path: PathBuf,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> SourceFile<T> {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Loads `RonAsset` files as assets.
+pub struct RonLoader<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for RonLoader<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: RonAsset> AssetLoader for RonLoader<T> {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let content = std::str::from_utf8(bytes)?;
+            let value = T::from_ron(content).map_err(|err| err.with_path(load_context.path()))?;
+            load_context.set_default_asset(LoadedAsset::new(value));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        T::EXTENSIONS
+    }
+}
+
+struct Watched<T: Asset> {
+    handle: Handle<T>,
+    path: PathBuf,
+}
+
+/// Watches the `SourceFile<T>` and applies every valid change to the `T`
+/// resource. Invalid edits are rejected by the loader (logged) and the
+/// running value is kept.
+///
+/// The asset server only watches its own folder, so files elsewhere, or not
+/// matching `T::EXTENSIONS`, are not reloaded.
+///
+/// Needs the asset server, so it is only part of the windowed game.
+pub struct ReloadPlugin<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for ReloadPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: RonAsset> Plugin for ReloadPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<T>()
+            .init_asset_loader::<RonLoader<T>>()
+            .add_startup_system(watch_system::<T>)
+            .add_system(reload_system::<T>);
+    }
+}
+
+fn watch_system<T: RonAsset>(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    file: Option<Res<SourceFile<T>>>,
+) {
+    let file = match file {
+        Some(file) => file,
+        None => return,
+    };
+    match asset_path(&file.path, T::EXTENSIONS) {
+        Some(path) => commands.insert_resource(Watched::<T> {
+            handle: asset_server.load(path.as_path()),
+            path: file.path.clone(),
+        }),
+        None => warn!(
+            "not watching {:?}, only {:?} files in the asset folder are reloaded",
+            file.path,
+            T::EXTENSIONS
+        ),
+    }
+}
+
+fn reload_system<T: RonAsset>(
+    mut events: EventReader<AssetEvent<T>>,
+    watched: Option<Res<Watched<T>>>,
+    assets: Res<Assets<T>>,
+    mut value: ResMut<T>,
+) {
+    let watched = match watched {
+        Some(watched) => watched,
+        None => return,
+    };
+    for event in events.iter() {
+        // the first load matches what was read at startup, only apply edits
+        if let AssetEvent::Modified { handle: modified } = event {
+            if modified == &watched.handle {
+                if let Some(new_value) = assets.get(modified) {
+                    *value = new_value.clone();
+                    info!("reloaded {:?}", watched.path);
+                }
+            }
+        }
+    }
+}
+
+/// `path` relative to the asset folder, `None` when it lies outside of it or
+/// its name does not end with one of `extensions`.
+fn asset_path(path: &Path, extensions: &[&str]) -> Option<PathBuf> {
+    let path = fs::canonicalize(path).ok()?;
+    let dir = fs::canonicalize(asset_dir()).ok()?;
+    let relative = path.strip_prefix(dir).ok()?;
+    let name = relative.file_name()?.to_str()?;
+    extensions
+        .iter()
+        .any(|extension| name.ends_with(&format!(".{extension}")))
+        .then(|| relative.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::CONFIG_FILE, wave::script::WAVES_FILE};
+
+    #[test]
+    fn only_matching_files_in_the_asset_folder_are_watched() {
+        let config = asset_dir().join(CONFIG_FILE);
+        assert_eq!(
+            asset_path(&config, GameConfig::EXTENSIONS),
+            Some(PathBuf::from(CONFIG_FILE))
+        );
+        assert_eq!(asset_path(&config, WaveScript::EXTENSIONS), None);
+
+        let waves = asset_dir().join(WAVES_FILE);
+        assert_eq!(
+            asset_path(&waves, WaveScript::EXTENSIONS),
+            Some(PathBuf::from(WAVES_FILE))
+        );
+
+        assert_eq!(asset_path(Path::new(file!()), &["rs"]), None);
+    }
+}
@@ -0,0 +1,480 @@
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use bevy::{app::AppExit, input::InputSystem, prelude::*, winit::WinitSettings};
+
+use crate::{rng::GameRng, score::Score, EnemyCount, PlayerState};
+
+const MAGIC: &[u8; 4] = b"RPLY";
+const VERSION: u8 = 1;
+/// frame record: time since startup (u64 nanos) + pressed (u16) + just pressed (u16)
+const FRAME_LEN: usize = 12;
+
+/// Keys the game reads, in bit order of the recorded masks.
+const REPLAY_KEYS: [KeyCode; 8] = [
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::Up,
+    KeyCode::Down,
+    KeyCode::Space,
+    KeyCode::Return,
+    KeyCode::Escape,
+    KeyCode::B,
+];
+
+// region: --- Replay File
+
+#[derive(Clone, Copy)]
+pub struct ReplayFrame {
+    pub since_startup: Duration,
+    pub pressed: u16,
+    pub just_pressed: u16,
+}
+
+/// A recorded run: seed, per-frame clock and keyboard state, and the
+/// game state reached at the end (`key=value` pairs).
+#[derive(Clone, Default)]
+pub struct Replay {
+    pub seed: u64,
+    pub frames: Vec<ReplayFrame>,
+    pub summary: Vec<(String, String)>,
+}
+
+impl Replay {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let bytes = fs::read(path)?;
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(17 + self.frames.len() * FRAME_LEN);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+        for frame in &self.frames {
+            bytes.extend_from_slice(&(frame.since_startup.as_nanos() as u64).to_le_bytes());
+            bytes.extend_from_slice(&frame.pressed.to_le_bytes());
+            bytes.extend_from_slice(&frame.just_pressed.to_le_bytes());
+        }
+        for (key, value) in &self.summary {
+            bytes.extend_from_slice(format!("{key}={value}\n").as_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        let invalid = |msg: &str| io::Error::new(ErrorKind::InvalidData, msg.to_string());
+
+        if bytes.len() < 17 || &bytes[0..4] != MAGIC {
+            return Err(invalid("not a replay file"));
+        }
+        if bytes[4] != VERSION {
+            return Err(invalid("unsupported replay version"));
+        }
+        let seed = u64::from_le_bytes(bytes[5..13].try_into().unwrap());
+        let count = u32::from_le_bytes(bytes[13..17].try_into().unwrap()) as usize;
+
+        let frames_end = 17 + count * FRAME_LEN;
+        if bytes.len() < frames_end {
+            return Err(invalid("replay file truncated"));
+        }
+        let frames = bytes[17..frames_end]
+            .chunks_exact(FRAME_LEN)
+            .map(|chunk| ReplayFrame {
+                since_startup: Duration::from_nanos(u64::from_le_bytes(
+                    chunk[0..8].try_into().unwrap(),
+                )),
+                pressed: u16::from_le_bytes(chunk[8..10].try_into().unwrap()),
+                just_pressed: u16::from_le_bytes(chunk[10..12].try_into().unwrap()),
+            })
+            .collect();
+
+        let trailer = std::str::from_utf8(&bytes[frames_end..])
+            .map_err(|_| invalid("replay summary is not utf-8"))?;
+        let summary = trailer
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+
+        Ok(Self {
+            seed,
+            frames,
+            summary,
+        })
+    }
+}
+
+// endregion: --- Replay File
+
+// region: --- Replay Plugin
+
+/// `--record <path>` or `--replay <path>` from the command line.
+pub enum ReplayConfig {
+    Record(PathBuf),
+    Playback(PathBuf),
+}
+
+impl ReplayConfig {
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Option<Self> {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--record" => return args.next().map(|path| Self::Record(path.into())),
+                "--replay" => return args.next().map(|path| Self::Playback(path.into())),
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+pub enum ReplayPlugin {
+    Record(PathBuf),
+    /// Plays back a loaded replay, seeding `GameRng` from it.
+    Playback(Replay),
+}
+
+struct ReplayRecorder {
+    path: PathBuf,
+    replay: Replay,
+}
+
+/// Whether a playback ended away from its recorded summary. Shared with
+/// `main`, which sets the exit code from it once the app has quit.
+#[derive(Clone, Default)]
+pub struct ReplayOutcome(Arc<AtomicBool>);
+
+impl ReplayOutcome {
+    pub fn mismatched(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+struct ReplayPlayback {
+    replay: Replay,
+    cursor: usize,
+    clock: Time,
+    finished: bool,
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        match self {
+            ReplayPlugin::Record(path) => {
+                app.insert_resource(ReplayRecorder {
+                    path: path.clone(),
+                    replay: Replay::default(),
+                })
+                .add_system_to_stage(
+                    CoreStage::PreUpdate,
+                    replay_record_system.after(InputSystem),
+                )
+                .add_system_to_stage(CoreStage::Last, replay_save_system);
+            }
+            ReplayPlugin::Playback(replay) => {
+                // keep a ReplayOutcome inserted beforehand, `main` reads it
+                app.init_resource::<ReplayOutcome>()
+                    // hand control back to `main` once done, also with a window
+                    .insert_resource(WinitSettings {
+                        return_from_run: true,
+                        ..WinitSettings::game()
+                    })
+                    .insert_resource(GameRng::with_seed(replay.seed))
+                    .insert_resource(ReplayPlayback {
+                        replay: replay.clone(),
+                        cursor: 0,
+                        clock: Time::default(),
+                        finished: false,
+                    })
+                    .add_system_to_stage(
+                        CoreStage::PreUpdate,
+                        replay_playback_system.after(InputSystem),
+                    )
+                    .add_system_to_stage(CoreStage::Last, replay_check_system);
+            }
+        }
+    }
+}
+
+/// Game state compared between the recording and its playback.
+pub(crate) fn replay_summary(
+    frames: usize,
+    enemy_count: &EnemyCount,
+    player_state: &PlayerState,
+    score: &Score,
+) -> Vec<(String, String)> {
+    vec![
+        ("frames".to_string(), frames.to_string()),
+        ("enemy_count".to_string(), enemy_count.0.to_string()),
+        ("player_on".to_string(), player_state.on.to_string()),
+        ("lives".to_string(), player_state.lives.to_string()),
+        ("score".to_string(), score.value.to_string()),
+    ]
+}
+
+fn replay_record_system(
+    mut recorder: ResMut<ReplayRecorder>,
+    kb: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    rng: Res<GameRng>,
+) {
+    let (mut pressed, mut just_pressed) = (0, 0);
+    for (i, key) in REPLAY_KEYS.iter().enumerate() {
+        if kb.pressed(*key) {
+            pressed |= 1 << i;
+        }
+        if kb.just_pressed(*key) {
+            just_pressed |= 1 << i;
+        }
+    }
+
+    let replay = &mut recorder.replay;
+    replay.seed = rng.seed();
+    replay.frames.push(ReplayFrame {
+        since_startup: time.time_since_startup(),
+        pressed,
+        just_pressed,
+    });
+}
+
+fn replay_save_system(
+    mut recorder: ResMut<ReplayRecorder>,
+    mut exit_events: EventReader<AppExit>,
+    enemy_count: Res<EnemyCount>,
+    player_state: Res<PlayerState>,
+    score: Res<Score>,
+) {
+    if exit_events.iter().next().is_none() {
+        return;
+    }
+
+    let frames = recorder.replay.frames.len();
+    recorder.replay.summary = replay_summary(frames, &enemy_count, &player_state, &score);
+    match recorder.replay.save(&recorder.path) {
+        Ok(()) => info!("replay saved to {:?} ({frames} frames)", recorder.path),
+        Err(err) => error!("failed to save replay to {:?}: {err}", recorder.path),
+    }
+}
+
+fn replay_playback_system(
+    mut playback: ResMut<ReplayPlayback>,
+    mut kb: ResMut<Input<KeyCode>>,
+    mut time: ResMut<Time>,
+) {
+    let frame = match playback.replay.frames.get(playback.cursor) {
+        Some(frame) => *frame,
+        None => return,
+    };
+    playback.cursor += 1;
+
+    // replace the real clock with the recorded one
+    let instant = playback.clock.startup() + frame.since_startup;
+    playback.clock.update_with_instant(instant);
+    *time = playback.clock.clone();
+
+    // replace the keyboard with the recorded keys
+    for (i, key) in REPLAY_KEYS.iter().enumerate() {
+        let bit = 1 << i;
+        kb.reset(*key);
+        if frame.pressed & bit != 0 {
+            kb.press(*key);
+            if frame.just_pressed & bit == 0 {
+                kb.clear_just_pressed(*key);
+            }
+        } else if frame.just_pressed & bit != 0 {
+            // pressed and released within the frame
+            kb.press(*key);
+            kb.release(*key);
+        }
+    }
+}
+
+fn replay_check_system(
+    mut playback: ResMut<ReplayPlayback>,
+    outcome: Res<ReplayOutcome>,
+    mut exit: EventWriter<AppExit>,
+    enemy_count: Res<EnemyCount>,
+    player_state: Res<PlayerState>,
+    score: Res<Score>,
+) {
+    if playback.finished || playback.cursor < playback.replay.frames.len() {
+        return;
+    }
+    playback.finished = true;
+
+    let actual = replay_summary(playback.cursor, &enemy_count, &player_state, &score);
+    let mut mismatches = 0;
+    for (key, expected) in &playback.replay.summary {
+        let value = actual.iter().find(|(k, _)| k == key).map(|(_, v)| v);
+        if value != Some(expected) {
+            error!("replay mismatch: {key} expected {expected}, got {value:?}");
+            mismatches += 1;
+        }
+    }
+
+    if mismatches > 0 {
+        outcome.0.store(true, Ordering::Relaxed);
+    } else {
+        info!(
+            "replay finished ({} frames), final state matches",
+            playback.cursor
+        );
+    }
+    exit.send(AppExit);
+}
+
+// endregion: --- Replay Plugin
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        headless::{HeadlessConfig, HeadlessPlugin},
+        state::GameState,
+        GamePlugin,
+    };
+
+    fn sample() -> Replay {
+        Replay {
+            seed: 42,
+            frames: vec![
+                ReplayFrame {
+                    since_startup: Duration::from_millis(16),
+                    pressed: 0b1_0001,
+                    just_pressed: 0b1_0000,
+                },
+                ReplayFrame {
+                    since_startup: Duration::from_millis(33),
+                    pressed: 0,
+                    just_pressed: 0b100_0000,
+                },
+            ],
+            summary: vec![
+                ("frames".to_string(), "2".to_string()),
+                ("score".to_string(), "300".to_string()),
+            ],
+        }
+    }
+
+    fn error_message(bytes: &[u8]) -> String {
+        match Replay::from_bytes(bytes) {
+            Ok(_) => panic!("replay should not load"),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let replay = sample();
+        let loaded = Replay::from_bytes(&replay.to_bytes()).unwrap();
+
+        assert_eq!(loaded.seed, replay.seed);
+        assert_eq!(loaded.frames.len(), replay.frames.len());
+        for (loaded, frame) in loaded.frames.iter().zip(&replay.frames) {
+            assert_eq!(loaded.since_startup, frame.since_startup);
+            assert_eq!(loaded.pressed, frame.pressed);
+            assert_eq!(loaded.just_pressed, frame.just_pressed);
+        }
+        assert_eq!(loaded.summary, replay.summary);
+    }
+
+    #[test]
+    fn summary_skips_lines_without_value() {
+        let mut bytes = Replay::default().to_bytes();
+        bytes.extend_from_slice(b"lives=2\nnot a pair\nscore=100\n");
+        let loaded = Replay::from_bytes(&bytes).unwrap();
+
+        assert_eq!(
+            loaded.summary,
+            vec![
+                ("lives".to_string(), "2".to_string()),
+                ("score".to_string(), "100".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_magic() {
+        let mut bytes = sample().to_bytes();
+        bytes[0..4].copy_from_slice(b"NOPE");
+        assert_eq!(error_message(&bytes), "not a replay file");
+    }
+
+    #[test]
+    fn bad_version() {
+        let mut bytes = sample().to_bytes();
+        bytes[4] = VERSION + 1;
+        assert_eq!(error_message(&bytes), "unsupported replay version");
+    }
+
+    #[test]
+    fn truncated_frame() {
+        // cut inside the last frame, the summary is dropped along with it
+        let replay = Replay {
+            summary: Vec::new(),
+            ..sample()
+        };
+        let bytes = replay.to_bytes();
+        assert_eq!(
+            error_message(&bytes[..bytes.len() - FRAME_LEN / 2]),
+            "replay file truncated"
+        );
+    }
+
+    #[test]
+    fn truncated_header() {
+        assert_eq!(
+            error_message(&sample().to_bytes()[..10]),
+            "not a replay file"
+        );
+    }
+
+    #[test]
+    fn headless_recording_plays_back() {
+        let frames = 60 * 10;
+        let mut app = App::new();
+        app.insert_resource(GameRng::with_seed(3))
+            .add_plugin(HeadlessPlugin(HeadlessConfig::default()))
+            .add_plugin(GamePlugin)
+            .add_plugin(ReplayPlugin::Record(PathBuf::new()));
+        for _ in 0..frames {
+            app.update();
+        }
+        let world = &app.world;
+        let mut replay = world.resource::<ReplayRecorder>().replay.clone();
+        replay.summary =
+            replay_summary(frames, world.resource(), world.resource(), world.resource());
+
+        // as `--headless --replay` runs it, the game only starts if the
+        // recording has the key press for it
+        let config = HeadlessConfig {
+            autostart: false,
+            ..Default::default()
+        };
+        let mut app = App::new();
+        app.add_plugin(HeadlessPlugin(config))
+            .add_plugin(GamePlugin)
+            .add_plugin(ReplayPlugin::Playback(replay));
+        for _ in 0..frames {
+            app.update();
+        }
+
+        let state = app.world.resource::<State<GameState>>();
+        assert_eq!(state.current(), &GameState::Playing);
+        assert!(!app.world.resource::<ReplayOutcome>().mismatched());
+    }
+}
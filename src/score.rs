@@ -0,0 +1,198 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::EnemyKind, enemy::formation::Formation, state::GameState, GameClock, GameSystem,
+};
+
+// region: --- Score Constants
+
+/// extra points, in percent, for an enemy shot down while flying in or diving
+const ENTERING_BONUS: u32 = 50;
+
+/// seconds between two kills to keep the chain going
+const CHAIN_WINDOW: f64 = 1.5;
+/// kills needed in a chain for each multiplier step
+const CHAIN_STEP: u32 = 3;
+const MULTIPLIER_MAX: u32 = 5;
+
+// endregion: --- Score Constants
+
+/// Sent for every enemy destroyed by the player.
+pub struct EnemyKilled {
+    pub position: Vec3,
+    pub kind: EnemyKind,
+    /// points of the kind, from `EnemyKindConfig`
+    pub points: u32,
+    pub formation: Formation,
+}
+
+impl EnemyKilled {
+    /// Base points, before the chain multiplier.
+    pub fn points(&self) -> u32 {
+        if self.formation.orbiting {
+            self.points
+        } else {
+            self.points + self.points * ENTERING_BONUS / 100
+        }
+    }
+}
+
+/// Sent for anything else the player destroys that is worth points, like
+/// boss parts.
+pub struct TargetDestroyed {
+    pub points: u32,
+}
+
+#[derive(Default)]
+pub struct Score {
+    pub value: u32,
+    /// consecutive kills, each within `CHAIN_WINDOW` of the previous one
+    pub chain: u32,
+    last_kill: f64,
+}
+
+impl Score {
+    pub fn multiplier(&self) -> u32 {
+        (1 + self.chain.saturating_sub(1) / CHAIN_STEP).min(MULTIPLIER_MAX)
+    }
+
+    /// Adds `points` times the chain multiplier, returns the points awarded.
+    pub fn kill(&mut self, points: u32, time: f64) -> u32 {
+        if self.chain > 0 && time - self.last_kill <= CHAIN_WINDOW {
+            self.chain += 1;
+        } else {
+            self.chain = 1;
+        }
+        self.last_kill = time;
+
+        let awarded = points * self.multiplier();
+        self.value += awarded;
+        awarded
+    }
+
+    /// Adds `points` as they are, leaving the chain alone.
+    pub fn bonus(&mut self, points: u32) {
+        self.value += points;
+    }
+
+    pub fn break_chain(&mut self) {
+        self.chain = 0;
+    }
+}
+
+/// Best score seen so far.
+#[derive(Default)]
+pub struct HighScore(pub u32);
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Score>()
+            .init_resource::<HighScore>()
+            .add_event::<EnemyKilled>()
+            .add_event::<TargetDestroyed>()
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .label(GameSystem::Cleanup)
+                    .after(GameSystem::Collision)
+                    .with_system(score_system),
+            );
+    }
+}
+
+fn score_system(
+    clock: Res<GameClock>,
+    mut score: ResMut<Score>,
+    mut high_score: ResMut<HighScore>,
+    mut events: EventReader<EnemyKilled>,
+    mut target_events: EventReader<TargetDestroyed>,
+) {
+    // game time, a pause does not run the chain window out
+    let now = clock.0;
+    let points = events
+        .iter()
+        .map(|event| event.points())
+        .chain(target_events.iter().map(|event| event.points));
+    for points in points {
+        score.kill(points, now);
+    }
+    // also catches points added outside of kills, like bonuses
+    if score.value > high_score.0 {
+        high_score.0 = score.value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn killed(orbiting: bool) -> EnemyKilled {
+        EnemyKilled {
+            position: Vec3::ZERO,
+            kind: EnemyKind::Grunt,
+            points: 100,
+            formation: Formation {
+                start: (0., 0.),
+                radius: (100., 100.),
+                pivot: (0., 0.),
+                speed: 1.,
+                angle: 0.,
+                orbiting,
+            },
+        }
+    }
+
+    #[test]
+    fn chain_holds_up_to_the_window() {
+        let mut score = Score::default();
+        score.kill(100, 0.);
+        // right on the edge still counts
+        score.kill(100, CHAIN_WINDOW);
+        assert_eq!(score.chain, 2);
+
+        score.kill(100, CHAIN_WINDOW * 2. + 0.01);
+        assert_eq!(score.chain, 1);
+    }
+
+    #[test]
+    fn multiplier_steps_up_and_caps() {
+        let mut score = Score::default();
+        for i in 0..CHAIN_STEP {
+            assert_eq!(score.kill(100, i as f64 * 0.1), 100);
+        }
+        assert_eq!(score.kill(100, 1.), 200);
+
+        for i in 0..100 {
+            score.kill(100, 1. + i as f64 * 0.1);
+        }
+        assert_eq!(score.multiplier(), MULTIPLIER_MAX);
+        assert_eq!(score.kill(100, 11.), 100 * MULTIPLIER_MAX);
+    }
+
+    #[test]
+    fn broken_chain_starts_over() {
+        let mut score = Score::default();
+        for i in 0..10 {
+            score.kill(100, i as f64 * 0.1);
+        }
+        score.break_chain();
+        assert_eq!(score.kill(100, 1.1), 100);
+        assert_eq!(score.chain, 1);
+    }
+
+    #[test]
+    fn bonus_leaves_the_chain_alone() {
+        let mut score = Score::default();
+        score.kill(100, 0.);
+        score.bonus(500);
+        assert_eq!(score.value, 600);
+        assert_eq!(score.chain, 1);
+    }
+
+    #[test]
+    fn entering_enemies_are_worth_more() {
+        assert_eq!(killed(true).points(), 100);
+        assert_eq!(killed(false).points(), 100 + ENTERING_BONUS);
+    }
+}
@@ -0,0 +1,202 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{Enemy, Explosion, ExplosionToSpawn, Laser, Player},
+    config::GameConfig,
+    high_scores::HighScoreTable,
+    power_up::PowerUp,
+    score::Score,
+    EnemyCount, GameClock, GameSystem, GameTextures, PlayerState, Wave,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GameState {
+    Menu,
+    Playing,
+    /// pushed on top of `Playing`, popping it resumes the game
+    Paused,
+    GameOver,
+    /// typing initials for a score that made it into the high-score table
+    NameEntry,
+}
+
+/// Marker for the text shown while in a non-playing state.
+#[derive(Component)]
+pub(crate) struct StateText;
+
+pub struct StatePlugin;
+
+impl Plugin for StatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state(GameState::Menu)
+            .add_system_set(SystemSet::on_enter(GameState::Menu).with_system(menu_setup_system))
+            .add_system_set(SystemSet::on_update(GameState::Menu).with_system(menu_input_system))
+            .add_system_set(
+                SystemSet::on_exit(GameState::Menu).with_system(state_text_cleanup_system),
+            )
+            .add_system_set(SystemSet::on_enter(GameState::Playing).with_system(game_reset_system))
+            .add_system_set(
+                // once the frame has played out
+                SystemSet::on_update(GameState::Playing).with_system(
+                    pause_input_system
+                        .label(GameSystem::Cleanup)
+                        .after(GameSystem::Collision),
+                ),
+            )
+            .add_system_set(SystemSet::on_enter(GameState::Paused).with_system(pause_setup_system))
+            .add_system_set(
+                SystemSet::on_update(GameState::Paused).with_system(resume_input_system),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::Paused).with_system(state_text_cleanup_system),
+            )
+            .add_system_set(
+                SystemSet::on_enter(GameState::GameOver)
+                    .with_system(game_over_cleanup_system)
+                    .with_system(game_over_setup_system),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::GameOver).with_system(game_over_input_system),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::GameOver).with_system(state_text_cleanup_system),
+            );
+    }
+}
+
+pub(crate) fn spawn_state_text(
+    commands: &mut Commands,
+    game_textures: &GameTextures,
+    text: &str,
+) -> Entity {
+    commands
+        .spawn_bundle(
+            TextBundle::from_section(
+                text,
+                TextStyle {
+                    font: game_textures.font.clone(),
+                    font_size: 40.,
+                    color: Color::WHITE,
+                },
+            )
+            .with_text_alignment(TextAlignment::CENTER)
+            .with_style(Style {
+                margin: UiRect::all(Val::Auto),
+                ..Default::default()
+            }),
+        )
+        .insert(StateText)
+        .id()
+}
+
+pub(crate) fn state_text_cleanup_system(
+    mut commands: Commands,
+    query: Query<Entity, With<StateText>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// region: --- Menu
+
+fn menu_setup_system(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    table: Res<HighScoreTable>,
+) {
+    let text = format!("RUST INVADERS\n\npress Enter\n\n{}", table.lines());
+    spawn_state_text(&mut commands, &game_textures, &text);
+}
+
+fn menu_input_system(mut kb: ResMut<Input<KeyCode>>, mut state: ResMut<State<GameState>>) {
+    if kb.just_pressed(KeyCode::Return) {
+        // the state driver re-runs the stage right away, do not let the
+        // same key press leak into the next state
+        kb.reset(KeyCode::Return);
+        state.set(GameState::Playing).unwrap();
+    }
+}
+
+// endregion: --- Menu
+
+// region: --- Playing / Paused
+
+fn game_reset_system(mut commands: Commands, config: Res<GameConfig>) {
+    commands.insert_resource(GameClock::default());
+    commands.insert_resource(PlayerState::new(&config.player));
+    commands.insert_resource(EnemyCount(0));
+    commands.insert_resource(Score::default());
+    commands.insert_resource(Wave(1));
+}
+
+fn pause_input_system(mut kb: ResMut<Input<KeyCode>>, mut state: ResMut<State<GameState>>) {
+    if kb.just_pressed(KeyCode::Escape) {
+        kb.reset(KeyCode::Escape);
+        state.push(GameState::Paused).unwrap();
+    }
+}
+
+fn pause_setup_system(mut commands: Commands, game_textures: Res<GameTextures>) {
+    spawn_state_text(&mut commands, &game_textures, "PAUSED");
+}
+
+fn resume_input_system(mut kb: ResMut<Input<KeyCode>>, mut state: ResMut<State<GameState>>) {
+    if kb.just_pressed(KeyCode::Escape) {
+        kb.reset(KeyCode::Escape);
+        state.pop().unwrap();
+    }
+}
+
+// endregion: --- Playing / Paused
+
+// region: --- Game Over
+
+/// Everything a game leaves on the field.
+type GameEntities = Or<(
+    With<Enemy>,
+    With<Laser>,
+    With<Explosion>,
+    With<ExplosionToSpawn>,
+    With<Player>,
+    With<PowerUp>,
+)>;
+
+fn game_over_cleanup_system(mut commands: Commands, query: Query<Entity, GameEntities>) {
+    for entity in query.iter() {
+        // the player ship carries its shield sprite
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn game_over_setup_system(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    table: Res<HighScoreTable>,
+    score: Res<Score>,
+) {
+    let text = if table.qualifies(score.value) {
+        "GAME OVER\n\nNEW HIGH SCORE!\npress Enter"
+    } else {
+        "GAME OVER\n\npress Enter"
+    };
+    spawn_state_text(&mut commands, &game_textures, text);
+}
+
+fn game_over_input_system(
+    mut kb: ResMut<Input<KeyCode>>,
+    mut state: ResMut<State<GameState>>,
+    table: Res<HighScoreTable>,
+    score: Res<Score>,
+) {
+    if kb.just_pressed(KeyCode::Return) {
+        kb.reset(KeyCode::Return);
+        if table.qualifies(score.value) {
+            state.set(GameState::NameEntry).unwrap();
+        } else {
+            state.set(GameState::Menu).unwrap();
+        }
+    }
+}
+
+// endregion: --- Game Over
@@ -0,0 +1,233 @@
+pub mod script;
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    components::EnemyKind, enemy::formation::Formation, rng::RngSystem, state::GameState,
+    EnemyCount, GameSystem, GameTextures, Wave,
+};
+
+use self::script::{BossTemplate, FormationTemplate, SpawnEvent, WaveDefinition, WaveScript};
+
+/// seconds the "WAVE N" banner stays up before enemies start spawning
+const BANNER_DURATION: f32 = 2.;
+const BANNER_FONT_SIZE: f32 = 48.;
+
+// region: --- Wave Progress
+
+/// A spawn event of the current wave, its members entering one by one.
+pub(crate) struct SpawnGroup {
+    pub event: SpawnEvent,
+    pub kind: EnemyKind,
+    pub template: FormationTemplate,
+    pub spawned: u32,
+    /// wave time at which the next member is due
+    next_at: f32,
+    /// shared by every member, made when the first one spawns
+    pub formation: Option<Formation>,
+}
+
+impl SpawnGroup {
+    /// Counts one more member as spawned.
+    pub fn member_spawned(&mut self) {
+        self.spawned += 1;
+        self.next_at += self.event.interval;
+    }
+}
+
+/// Where the wave's boss is at.
+pub(crate) enum BossStage {
+    NoBoss,
+    /// shows up once the spawns are cleared
+    Waiting(BossTemplate),
+    Fighting,
+    Defeated,
+}
+
+/// The wave being played and how far along it is.
+pub struct WaveProgress {
+    pub definition: WaveDefinition,
+    groups: Vec<SpawnGroup>,
+    pub(crate) boss: BossStage,
+    /// seconds since the banner went away
+    elapsed: f32,
+    banner: Timer,
+}
+
+impl Default for WaveProgress {
+    fn default() -> Self {
+        Self::new(&WaveScript::default(), 1)
+    }
+}
+
+impl WaveProgress {
+    pub fn new(script: &WaveScript, number: u32) -> Self {
+        let definition = script.wave(number);
+        // references are checked when the script is loaded
+        let groups = definition
+            .spawns
+            .iter()
+            .filter_map(|event| {
+                Some(SpawnGroup {
+                    event: event.clone(),
+                    kind: EnemyKind::from_name(&event.kind)?,
+                    template: script.formations.get(&event.formation)?.clone(),
+                    spawned: 0,
+                    next_at: event.at,
+                    formation: None,
+                })
+            })
+            .collect();
+        let boss = match definition
+            .boss
+            .as_ref()
+            .and_then(|name| script.bosses.get(name))
+        {
+            Some(template) => BossStage::Waiting(template.clone()),
+            None => BossStage::NoBoss,
+        };
+        Self {
+            definition,
+            groups,
+            boss,
+            elapsed: 0.,
+            banner: Timer::from_seconds(BANNER_DURATION, false),
+        }
+    }
+
+    pub fn in_banner(&self) -> bool {
+        !self.banner.finished()
+    }
+
+    pub fn spawn_done(&self) -> bool {
+        self.groups
+            .iter()
+            .all(|group| group.spawned >= group.event.count)
+    }
+
+    /// Every enemy spawned and destroyed, boss included.
+    pub fn cleared(&self, enemy_count: u32) -> bool {
+        self.spawn_done()
+            && enemy_count == 0
+            && matches!(self.boss, BossStage::NoBoss | BossStage::Defeated)
+    }
+
+    /// Group whose next member is due, if any. The caller is expected to
+    /// spawn it right away, members held back by `enemy.max` do not pile up.
+    pub(crate) fn due_group(&mut self) -> Option<&mut SpawnGroup> {
+        if self.in_banner() {
+            return None;
+        }
+        let elapsed = self.elapsed;
+        let group = self
+            .groups
+            .iter_mut()
+            .find(|group| group.spawned < group.event.count && group.next_at <= elapsed)?;
+        group.next_at = elapsed;
+        Some(group)
+    }
+
+    /// Runs the banner, then the wave clock. Returns true when the banner ends.
+    fn tick(&mut self, delta: Duration) -> bool {
+        if self.in_banner() {
+            self.banner.tick(delta).just_finished()
+        } else {
+            self.elapsed += delta.as_secs_f32();
+            false
+        }
+    }
+}
+
+#[derive(Component)]
+struct WaveBanner;
+
+// endregion: --- Wave Progress
+
+pub struct WavePlugin;
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        // keep a WaveScript inserted beforehand (e.g. from `--waves`)
+        app.init_resource::<WaveScript>()
+            .init_resource::<WaveProgress>()
+            .add_system_set(SystemSet::on_enter(GameState::Playing).with_system(wave_start_system))
+            // spawns are due on the wave clock
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing).with_system(
+                    wave_system
+                        .label(GameSystem::Spawn)
+                        .after(GameSystem::Input)
+                        .before(RngSystem::EnemySpawn),
+                ),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::Playing).with_system(wave_banner_cleanup_system),
+            );
+    }
+}
+
+fn spawn_wave_banner(commands: &mut Commands, game_textures: &GameTextures, number: u32) {
+    commands
+        .spawn_bundle(
+            TextBundle::from_section(
+                format!("WAVE {number}"),
+                TextStyle {
+                    font: game_textures.font.clone(),
+                    font_size: BANNER_FONT_SIZE,
+                    color: Color::WHITE,
+                },
+            )
+            .with_text_alignment(TextAlignment::CENTER)
+            .with_style(Style {
+                margin: UiRect::all(Val::Auto),
+                ..Default::default()
+            }),
+        )
+        .insert(WaveBanner);
+}
+
+fn wave_start_system(
+    mut commands: Commands,
+    script: Res<WaveScript>,
+    game_textures: Res<GameTextures>,
+) {
+    // `Wave` itself is reset to 1 by `game_reset_system`
+    commands.insert_resource(WaveProgress::new(&script, 1));
+    spawn_wave_banner(&mut commands, &game_textures, 1);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn wave_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    script: Res<WaveScript>,
+    game_textures: Res<GameTextures>,
+    enemy_count: Res<EnemyCount>,
+    mut wave: ResMut<Wave>,
+    mut progress: ResMut<WaveProgress>,
+    banner_query: Query<Entity, With<WaveBanner>>,
+) {
+    if progress.tick(time.delta()) {
+        for entity in banner_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    if progress.in_banner() {
+        return;
+    }
+
+    // wave cleared, on to the next one
+    if progress.cleared(enemy_count.0) {
+        wave.0 += 1;
+        *progress = WaveProgress::new(&script, wave.0);
+        spawn_wave_banner(&mut commands, &game_textures, wave.0);
+    }
+}
+
+fn wave_banner_cleanup_system(mut commands: Commands, query: Query<Entity, With<WaveBanner>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,936 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::reflect::TypeUuid;
+use serde::Deserialize;
+
+use crate::{
+    components::EnemyKind,
+    config::ConfigError,
+    enemy::formation::FormationShape,
+    pattern::{Aim, BulletPattern},
+};
+
+/// Wave script looked up in the asset folder when no `--waves` is given.
+pub const WAVES_FILE: &str = "game.waves.ron";
+
+/// shortest gap between two spawns, however hard the wave gets
+const SPAWN_INTERVAL_MIN: f32 = 0.25;
+const SPEED_MAX: f32 = 2.;
+
+// region: --- Script Types
+
+/// Waves played in order, the last one repeats with rising difficulty.
+#[derive(Clone, Debug, Deserialize, TypeUuid)]
+#[uuid = "0c8e4b52-7d1a-4f0e-b6a3-5e9f2c71d4a8"]
+pub struct WaveScript {
+    /// formation templates, referenced by name from the spawn events
+    pub formations: HashMap<String, FormationTemplate>,
+    /// boss templates, referenced by name from the waves
+    #[serde(default)]
+    pub bosses: HashMap<String, BossTemplate>,
+    pub waves: Vec<WaveDefinition>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct FormationTemplate {
+    pub shape: FormationShape,
+    /// multiplier on `base_speed`
+    #[serde(default = "one")]
+    pub speed: f32,
+}
+
+/// What a single wave spawns and how aggressive it is.
+#[derive(Clone, Debug, Deserialize)]
+pub struct WaveDefinition {
+    /// multiplier on `enemy.fire_rate`
+    #[serde(default = "one_f64")]
+    pub fire_rate: f64,
+    /// multiplier on the speed of every formation
+    #[serde(default = "one")]
+    pub speed: f32,
+    pub spawns: Vec<SpawnEvent>,
+    /// name of a `BossTemplate`, showing up once the spawns are cleared
+    #[serde(default)]
+    pub boss: Option<String>,
+}
+
+/// One formation entering the screen, member after member.
+#[derive(Clone, Debug, Deserialize)]
+pub struct SpawnEvent {
+    /// seconds after the wave banner went away
+    pub at: f32,
+    /// name of a `FormationTemplate`
+    pub formation: String,
+    /// `EnemyKind` variant name
+    pub kind: String,
+    /// members of the formation
+    pub count: u32,
+    #[serde(default)]
+    pub side: Side,
+    /// seconds between two members
+    #[serde(default = "one")]
+    pub interval: f32,
+}
+
+/// Window edge a formation flies in from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum Side {
+    Left,
+    Right,
+    /// picked by the game rng for each formation
+    #[default]
+    Random,
+}
+
+/// Large enemy made of several hit zones, with phases changing as it takes damage.
+#[derive(Clone, Debug, Deserialize)]
+pub struct BossTemplate {
+    /// tint of the body sprite (`sprites.boss`)
+    #[serde(default = "white")]
+    pub color: (f32, f32, f32),
+    /// multiplier on `sprite_scale`
+    #[serde(default = "one")]
+    pub scale: f32,
+    /// bonus once every part is destroyed
+    #[serde(default)]
+    pub points: u32,
+    pub parts: Vec<BossPartTemplate>,
+    /// highest `below` first
+    pub phases: Vec<BossPhase>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BossPartTemplate {
+    /// from the body center, in pixels
+    pub offset: (f32, f32),
+    pub size: (f32, f32),
+    pub hp: u32,
+    #[serde(default)]
+    pub points: u32,
+    #[serde(default = "white")]
+    pub color: (f32, f32, f32),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BossPhase {
+    /// starts once the boss is down to this fraction of its hit points
+    pub below: f32,
+    pub movement: BossMovement,
+    /// times per second the pattern is fired
+    pub fire_rate: f32,
+    #[serde(default)]
+    pub pattern: BulletPattern,
+}
+
+/// Path of the boss body around its resting position.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub enum BossMovement {
+    Hover,
+    /// side to side, `width` pixels each way
+    Sweep {
+        width: f32,
+        period: f32,
+    },
+    Circle {
+        radius: f32,
+        period: f32,
+    },
+}
+
+fn one() -> f32 {
+    1.
+}
+
+fn one_f64() -> f64 {
+    1.
+}
+
+fn white() -> (f32, f32, f32) {
+    (1., 1., 1.)
+}
+
+impl Default for WaveScript {
+    /// Same waves as `assets/game.waves.ron`.
+    fn default() -> Self {
+        let template = |shape, speed| FormationTemplate { shape, speed };
+        let spawn = |at, formation: &str, kind: &str, count, side, interval| SpawnEvent {
+            at,
+            formation: formation.to_string(),
+            kind: kind.to_string(),
+            count,
+            side,
+            interval,
+        };
+        let wave = |fire_rate, speed, spawns| WaveDefinition {
+            fire_rate,
+            speed,
+            spawns,
+            boss: None,
+        };
+        let part = |offset, size, hp, points, color| BossPartTemplate {
+            offset,
+            size,
+            hp,
+            points,
+            color,
+        };
+        let phase = |below, movement, fire_rate, pattern| BossPhase {
+            below,
+            movement,
+            fire_rate,
+            pattern,
+        };
+
+        Self {
+            formations: HashMap::from([
+                ("ellipse".to_string(), template(FormationShape::Ellipse, 1.)),
+                ("circle".to_string(), template(FormationShape::Circle, 1.)),
+                ("wide".to_string(), template(FormationShape::Wide, 1.1)),
+            ]),
+            bosses: HashMap::from([(
+                "mothership".to_string(),
+                BossTemplate {
+                    color: (0.8, 0.6, 1.),
+                    scale: 2.5,
+                    points: 5000,
+                    parts: vec![
+                        part((-90., 0.), (50., 40.), 12, 500, (1., 0.5, 0.3)),
+                        part((90., 0.), (50., 40.), 12, 500, (1., 0.5, 0.3)),
+                        part((0., -20.), (70., 50.), 20, 1000, (1., 0.2, 0.2)),
+                    ],
+                    phases: vec![
+                        phase(
+                            1.,
+                            BossMovement::Hover,
+                            1.,
+                            BulletPattern {
+                                ways: 5,
+                                spread: 1.2,
+                                ..Default::default()
+                            },
+                        ),
+                        phase(
+                            0.6,
+                            BossMovement::Sweep {
+                                width: 150.,
+                                period: 4.,
+                            },
+                            1.2,
+                            BulletPattern {
+                                aim: Aim::Player,
+                                ways: 3,
+                                spread: 0.5,
+                                burst: 3,
+                                burst_interval: 0.15,
+                                ..Default::default()
+                            },
+                        ),
+                        phase(
+                            0.3,
+                            BossMovement::Circle {
+                                radius: 80.,
+                                period: 3.,
+                            },
+                            4.,
+                            BulletPattern {
+                                aim: Aim::Spiral { turn: 2.5 },
+                                ways: 4,
+                                spread: 4.7,
+                                speed: 0.5,
+                                acceleration: 0.5,
+                                curve: 0.3,
+                                ..Default::default()
+                            },
+                        ),
+                    ],
+                },
+            )]),
+            waves: vec![
+                wave(
+                    1.,
+                    1.,
+                    vec![
+                        spawn(0., "ellipse", "Grunt", 2, Side::Right, 1.),
+                        spawn(3., "ellipse", "Grunt", 2, Side::Left, 1.),
+                    ],
+                ),
+                wave(
+                    1.2,
+                    1.,
+                    vec![
+                        spawn(0., "circle", "Grunt", 2, Side::Left, 0.9),
+                        spawn(2., "circle", "Grunt", 2, Side::Right, 0.9),
+                        spawn(4., "ellipse", "Grunt", 2, Side::Random, 0.9),
+                    ],
+                ),
+                wave(
+                    1.4,
+                    1.,
+                    vec![
+                        spawn(0., "wide", "Grunt", 3, Side::Left, 0.8),
+                        spawn(3., "wide", "Grunt", 3, Side::Right, 0.8),
+                        spawn(6., "circle", "Sniper", 3, Side::Random, 0.8),
+                    ],
+                ),
+                wave(
+                    1.6,
+                    1.1,
+                    vec![
+                        spawn(0., "ellipse", "Grunt", 3, Side::Left, 0.7),
+                        spawn(0., "ellipse", "Grunt", 3, Side::Right, 0.7),
+                        spawn(4., "circle", "Grunt", 3, Side::Random, 0.7),
+                        spawn(6., "wide", "Tank", 3, Side::Random, 0.7),
+                    ],
+                ),
+                WaveDefinition {
+                    boss: Some("mothership".to_string()),
+                    ..wave(
+                        1.8,
+                        1.2,
+                        vec![
+                            spawn(0., "circle", "Grunt", 4, Side::Left, 0.6),
+                            spawn(0., "circle", "Grunt", 4, Side::Right, 0.6),
+                            spawn(4., "wide", "Kamikaze", 4, Side::Random, 0.6),
+                            spawn(6., "ellipse", "Sniper", 4, Side::Random, 0.6),
+                        ],
+                    )
+                },
+            ],
+        }
+    }
+}
+
+impl WaveScript {
+    /// Definition of wave `number`, starting at 1.
+    pub fn wave(&self, number: u32) -> WaveDefinition {
+        let index = number.saturating_sub(1) as usize;
+        let last = self.waves.len() - 1;
+        match self.waves.get(index) {
+            Some(definition) => definition.clone(),
+            None => self.waves[last].harder((index - last) as u32),
+        }
+    }
+}
+
+impl WaveDefinition {
+    /// The same wave, `level` steps harder.
+    fn harder(&self, level: u32) -> Self {
+        let level_f = level as f32;
+        let quicker = 0.9_f32.powf(level_f);
+        Self {
+            fire_rate: self.fire_rate * (1. + 0.2 * level as f64),
+            speed: (self.speed * (1. + 0.05 * level_f)).min(SPEED_MAX),
+            spawns: self
+                .spawns
+                .iter()
+                .map(|spawn| SpawnEvent {
+                    at: spawn.at * quicker,
+                    count: spawn.count + level / 2,
+                    interval: (spawn.interval * quicker).max(SPAWN_INTERVAL_MIN),
+                    ..spawn.clone()
+                })
+                .collect(),
+            boss: self.boss.clone(),
+        }
+    }
+}
+
+// endregion: --- Script Types
+
+// region: --- Loading
+
+impl WaveScript {
+    /// Reads and validates a wave script, a missing file gives the built-in waves.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::read(path)
+    }
+
+    /// Like `load`, but a missing file is an error.
+    pub fn read(path: &Path) -> Result<Self, ConfigError> {
+        let content =
+            fs::read_to_string(path).map_err(|err| ConfigError::Io(path.to_path_buf(), err))?;
+        Self::from_ron(&content).map_err(|err| err.with_path(path))
+    }
+
+    pub fn from_ron(content: &str) -> Result<Self, ConfigError> {
+        let script: WaveScript = ron::from_str(content)
+            .map_err(|err| ConfigError::Parse(PathBuf::new(), err.to_string()))?;
+        script.validate().map_err(|errors| {
+            // point at the offending line, the messages only name the field
+            let tokens = tokenize(content);
+            let errors = errors
+                .into_iter()
+                .map(|err| match message_line(&tokens, &err) {
+                    Some(line) => format!("line {line}: {err}"),
+                    None => err,
+                })
+                .collect();
+            ConfigError::Invalid(PathBuf::new(), errors)
+        })?;
+        Ok(script)
+    }
+
+    /// `--waves <path>` from the command line.
+    pub fn path_from_args(args: impl IntoIterator<Item = String>) -> Option<PathBuf> {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--waves" {
+                return args.next().map(PathBuf::from);
+            }
+        }
+        None
+    }
+
+    /// `--lint-waves <path>` from the command line.
+    pub fn lint_path_from_args(args: impl IntoIterator<Item = String>) -> Option<PathBuf> {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--lint-waves" {
+                return args.next().map(PathBuf::from);
+            }
+        }
+        None
+    }
+
+    /// Checks values and references to templates and enemy kinds.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        let mut names: Vec<&String> = self.formations.keys().collect();
+        names.sort();
+        for name in names {
+            if self.formations[name].speed <= 0. {
+                errors.push(format!(
+                    "formations[\"{name}\"].speed must be greater than 0"
+                ));
+            }
+        }
+
+        let mut bosses: Vec<&String> = self.bosses.keys().collect();
+        bosses.sort();
+        for name in bosses {
+            let boss = &self.bosses[name];
+            let at = format!("bosses[\"{name}\"]");
+            if boss.scale <= 0. {
+                errors.push(format!("{at}.scale must be greater than 0"));
+            }
+            if boss.parts.is_empty() {
+                errors.push(format!("{at}.parts must not be empty"));
+            }
+            for (j, part) in boss.parts.iter().enumerate() {
+                if part.hp == 0 {
+                    errors.push(format!("{at}.parts[{j}].hp must be at least 1"));
+                }
+                if part.size.0 <= 0. || part.size.1 <= 0. {
+                    errors.push(format!("{at}.parts[{j}].size must be positive"));
+                }
+            }
+            match boss.phases.first() {
+                None => errors.push(format!("{at}.phases must not be empty")),
+                Some(first) if first.below != 1. => {
+                    errors.push(format!("{at}.phases[0].below must be 1 (full health)"))
+                }
+                _ => {}
+            }
+            if !boss
+                .phases
+                .windows(2)
+                .all(|pair| pair[0].below > pair[1].below)
+            {
+                errors.push(format!("{at}.phases must have a strictly decreasing below"));
+            }
+            for (j, phase) in boss.phases.iter().enumerate() {
+                if !(0. < phase.below && phase.below <= 1.) {
+                    errors.push(format!("{at}.phases[{j}].below must be in (0, 1]"));
+                }
+                if phase.fire_rate < 0. {
+                    errors.push(format!("{at}.phases[{j}].fire_rate must not be negative"));
+                }
+                errors.extend(phase.pattern.validate(&format!("{at}.phases[{j}].pattern")));
+                let period = match phase.movement {
+                    BossMovement::Hover => 1.,
+                    BossMovement::Sweep { period, .. } | BossMovement::Circle { period, .. } => {
+                        period
+                    }
+                };
+                if period <= 0. {
+                    errors.push(format!(
+                        "{at}.phases[{j}].movement period must be greater than 0"
+                    ));
+                }
+            }
+        }
+
+        if self.waves.is_empty() {
+            errors.push("waves must not be empty".to_string());
+        }
+        for (i, wave) in self.waves.iter().enumerate() {
+            if wave.fire_rate < 0. {
+                errors.push(format!("waves[{i}].fire_rate must not be negative"));
+            }
+            if wave.speed <= 0. {
+                errors.push(format!("waves[{i}].speed must be greater than 0"));
+            }
+            if wave.spawns.is_empty() {
+                errors.push(format!("waves[{i}].spawns must not be empty"));
+            }
+            if let Some(name) = &wave.boss {
+                if !self.bosses.contains_key(name) {
+                    errors.push(format!("waves[{i}]: unknown boss \"{name}\""));
+                }
+            }
+            for (j, spawn) in wave.spawns.iter().enumerate() {
+                let at = format!("waves[{i}].spawns[{j}]");
+                if !self.formations.contains_key(&spawn.formation) {
+                    let name = &spawn.formation;
+                    errors.push(format!("{at}: unknown formation \"{name}\""));
+                }
+                if EnemyKind::from_name(&spawn.kind).is_none() {
+                    let name = &spawn.kind;
+                    errors.push(format!("{at}: unknown kind \"{name}\""));
+                }
+                if spawn.count == 0 {
+                    errors.push(format!("{at}.count must be at least 1"));
+                }
+                if spawn.at < 0. {
+                    errors.push(format!("{at}.at must not be negative"));
+                }
+                if spawn.interval <= 0. {
+                    errors.push(format!("{at}.interval must be greater than 0"));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Piece of RON source, as much as needed to find where a value starts.
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    /// one of `()[]{}:,`
+    Punct(char),
+    /// contents of a string literal
+    Str(&'a str),
+    /// identifier, number or anything else standing on its own
+    Word(&'a str),
+}
+
+/// Tokens of `content` with their line (from 1), comments left out.
+fn tokenize(content: &str) -> Vec<(Token<'_>, usize)> {
+    let is_delimiter = |c: char| c.is_whitespace() || "()[]{}:,\"/".contains(c);
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut chars = content.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            '/' if matches!(chars.peek(), Some((_, '/'))) => {
+                while chars.next_if(|&(_, c)| c != '\n').is_some() {}
+            }
+            '/' if matches!(chars.peek(), Some((_, '*'))) => {
+                chars.next();
+                let mut prev = ' ';
+                for (_, c) in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            '"' => {
+                let first_line = line;
+                let mut end = content.len();
+                let mut escaped = false;
+                for (i, c) in chars.by_ref() {
+                    match c {
+                        '\n' => line += 1,
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        '"' => {
+                            end = i;
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                tokens.push((Token::Str(&content[start + 1..end]), first_line));
+            }
+            '(' | ')' | '[' | ']' | '{' | '}' | ':' | ',' => tokens.push((Token::Punct(c), line)),
+            _ => {
+                let end = loop {
+                    match chars.peek() {
+                        Some(&(i, c)) if is_delimiter(c) => break i,
+                        Some(_) => {
+                            chars.next();
+                        }
+                        None => break content.len(),
+                    }
+                };
+                tokens.push((Token::Word(&content[start..end]), line));
+            }
+        }
+    }
+    tokens
+}
+
+/// One step down a validation message's path.
+enum Step<'a> {
+    /// `.name`
+    Field(&'a str),
+    /// `[2]`
+    Index(usize),
+    /// `["name"]`
+    Key(&'a str),
+}
+
+/// The `waves[0].spawns[1]` path a validation message starts with.
+fn message_path(message: &str) -> Vec<Step<'_>> {
+    let mut steps = Vec::new();
+    let mut rest = message;
+    loop {
+        if let Some(after) = rest.strip_prefix("[\"") {
+            match after.split_once("\"]") {
+                Some((key, after)) => {
+                    steps.push(Step::Key(key));
+                    rest = after;
+                }
+                None => break,
+            }
+        } else if let Some(after) = rest.strip_prefix('[') {
+            match after.split_once(']') {
+                Some((index, after)) => match index.parse() {
+                    Ok(index) => {
+                        steps.push(Step::Index(index));
+                        rest = after;
+                    }
+                    Err(_) => break,
+                },
+                None => break,
+            }
+        } else {
+            let after = match rest.strip_prefix('.') {
+                Some(after) => after,
+                None if steps.is_empty() => rest,
+                None => break,
+            };
+            let len = after
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            if len == 0 {
+                break;
+            }
+            steps.push(Step::Field(&after[..len]));
+            rest = &after[len..];
+        }
+    }
+    steps
+}
+
+/// Where each item of the `(`, `[` or `{` at `open` starts.
+fn items(tokens: &[(Token, usize)], open: usize) -> Vec<usize> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut next_item = true;
+    for (i, (token, _)) in tokens.iter().enumerate().skip(open + 1) {
+        match token {
+            Token::Punct(')' | ']' | '}') if depth == 0 => break,
+            Token::Punct(',') if depth == 0 => {
+                next_item = true;
+                continue;
+            }
+            _ => {}
+        }
+        if depth == 0 && next_item {
+            items.push(i);
+            next_item = false;
+        }
+        match token {
+            Token::Punct('(' | '[' | '{') => depth += 1,
+            Token::Punct(')' | ']' | '}') => depth -= 1,
+            _ => {}
+        }
+    }
+    items
+}
+
+/// Line of the value a validation message is about, or of the closest
+/// enclosing one when it is not written out (a field left to its default).
+fn message_line(tokens: &[(Token, usize)], message: &str) -> Option<usize> {
+    // past `#![enable(...)]` attributes
+    let mut pos = 0;
+    while matches!(tokens.get(pos), Some((Token::Word(word), _)) if word.starts_with('#')) {
+        let mut depth = 0;
+        loop {
+            pos += 1;
+            match tokens.get(pos)?.0 {
+                Token::Punct('(' | '[' | '{') => depth += 1,
+                Token::Punct(')' | ']' | '}') => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                break;
+            }
+        }
+        pos += 1;
+    }
+
+    let mut line = None;
+    for step in message_path(message) {
+        // a struct or variant may be named before its fields, `Sweep(...)`
+        let open = match tokens.get(pos) {
+            Some((Token::Word(_), _)) => pos + 1,
+            _ => pos,
+        };
+        if !matches!(tokens.get(open), Some((Token::Punct('(' | '[' | '{'), _))) {
+            break;
+        }
+        let items = items(tokens, open);
+        let labelled = |label: Token| {
+            items.iter().copied().find(|&item| {
+                tokens[item].0 == label
+                    && matches!(tokens.get(item + 1), Some((Token::Punct(':'), _)))
+            })
+        };
+        let (item, value) = match step {
+            Step::Index(index) => match items.get(index) {
+                Some(&item) => (item, item),
+                None => break,
+            },
+            Step::Field(name) => match labelled(Token::Word(name)) {
+                Some(item) => (item, item + 2),
+                None => break,
+            },
+            Step::Key(key) => match labelled(Token::Str(key)) {
+                Some(item) => (item, item + 2),
+                None => break,
+            },
+        };
+        line = Some(tokens[item].1);
+        pos = value;
+    }
+    line
+}
+
+// endregion: --- Loading
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::asset_dir;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    fn definition() -> WaveDefinition {
+        WaveDefinition {
+            fire_rate: 1.,
+            speed: 1.,
+            spawns: vec![SpawnEvent {
+                at: 10.,
+                formation: "ellipse".to_string(),
+                kind: "Grunt".to_string(),
+                count: 4,
+                side: Side::Left,
+                interval: 1.,
+            }],
+            boss: Some("mothership".to_string()),
+        }
+    }
+
+    #[test]
+    fn built_in_and_shipped_scripts_are_valid() {
+        assert_eq!(WaveScript::default().validate(), Ok(()));
+        WaveScript::read(&asset_dir().join(WAVES_FILE)).unwrap();
+    }
+
+    #[test]
+    fn missing_file_loads_the_built_in_waves_but_does_not_read() {
+        let path = asset_dir().join("no-such.waves.ron");
+        let script = WaveScript::load(&path).unwrap();
+        assert_eq!(script.waves.len(), WaveScript::default().waves.len());
+        assert!(matches!(WaveScript::read(&path), Err(ConfigError::Io(..))));
+    }
+
+    #[test]
+    fn waves_path_only_from_args() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            WaveScript::path_from_args(args(&["game", "--headless"])),
+            None
+        );
+        assert_eq!(
+            WaveScript::path_from_args(args(&["game", "--waves", "mine.ron"])),
+            Some(PathBuf::from("mine.ron"))
+        );
+    }
+
+    #[test]
+    fn unknown_formation_is_reported() {
+        let mut script = WaveScript::default();
+        script.waves[0].spawns[1].formation = "zigzag".to_string();
+        assert_eq!(
+            script.validate(),
+            Err(vec![
+                "waves[0].spawns[1]: unknown formation \"zigzag\"".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn unknown_kind_is_reported() {
+        let mut script = WaveScript::default();
+        script.waves[0].spawns[0].kind = "grunt".to_string();
+        assert_eq!(
+            script.validate(),
+            Err(vec![
+                "waves[0].spawns[0]: unknown kind \"grunt\"".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn unknown_boss_is_reported() {
+        let mut script = WaveScript::default();
+        script.waves[0].boss = Some("dragon".to_string());
+        assert_eq!(
+            script.validate(),
+            Err(vec!["waves[0]: unknown boss \"dragon\"".to_string()])
+        );
+    }
+
+    #[test]
+    fn empty_waves_are_rejected() {
+        let mut script = WaveScript::default();
+        script.waves.clear();
+        assert_eq!(
+            script.validate(),
+            Err(vec!["waves must not be empty".to_string()])
+        );
+    }
+
+    #[test]
+    fn from_ron_reports_all_errors() {
+        let content = r#"(
+            formations: {},
+            waves: [(spawns: [(at: 0., formation: "circle", kind: "Ogre", count: 0)])],
+        )"#;
+        match WaveScript::from_ron(content) {
+            Err(ConfigError::Invalid(_, errors)) => assert_eq!(
+                errors,
+                vec![
+                    "line 3: waves[0].spawns[0]: unknown formation \"circle\"",
+                    "line 3: waves[0].spawns[0]: unknown kind \"Ogre\"",
+                    "line 3: waves[0].spawns[0].count must be at least 1",
+                ]
+            ),
+            other => panic!("expected validation errors, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn errors_point_at_their_line() {
+        let content = r#"#![enable(implicit_some)]
+// "waves": a comment naming a field
+(
+    formations: {
+        "circle": (shape: Circle, speed: 0.),
+    },
+    bosses: {
+        "drone": (
+            parts: [
+                (offset: (0., 0.), size: (10., 10.), hp: 1),
+                (offset: (0., 0.), size: (10., 10.), hp: 0),
+            ],
+            phases: [(below: 1., movement: Sweep(width: 10., period: 0.), fire_rate: 1.)],
+        ),
+    },
+    waves: [
+        (spawns: [(at: 0., formation: "circle", kind: "Grunt", count: 1)]),
+        (
+            speed: 0.,
+            spawns: [
+                (at: 0., formation: "circle", kind: "Grunt", count: 1),
+                (at: 0., formation: "zigzag", kind: "Grunt", count: 1),
+            ],
+            boss: "dragon",
+        ),
+    ],
+)"#;
+        match WaveScript::from_ron(content) {
+            Err(ConfigError::Invalid(_, errors)) => assert_eq!(
+                errors,
+                vec![
+                    "line 5: formations[\"circle\"].speed must be greater than 0",
+                    "line 11: bosses[\"drone\"].parts[1].hp must be at least 1",
+                    "line 13: bosses[\"drone\"].phases[0].movement period must be greater than 0",
+                    "line 19: waves[1].speed must be greater than 0",
+                    "line 18: waves[1]: unknown boss \"dragon\"",
+                    "line 22: waves[1].spawns[1]: unknown formation \"zigzag\"",
+                ]
+            ),
+            other => panic!("expected validation errors, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn harder_level_zero_is_unchanged() {
+        let harder = definition().harder(0);
+        assert_eq!(harder.fire_rate, 1.);
+        assert_eq!(harder.speed, 1.);
+        assert_eq!(harder.spawns[0].at, 10.);
+        assert_eq!(harder.spawns[0].count, 4);
+        assert_eq!(harder.spawns[0].interval, 1.);
+    }
+
+    #[test]
+    fn harder_ramps_up() {
+        let harder = definition().harder(2);
+        assert!((harder.fire_rate - 1.4).abs() < 1e-9);
+        assert!(close(harder.speed, 1.1));
+
+        let spawn = &harder.spawns[0];
+        assert!(close(spawn.at, 8.1));
+        assert!(close(spawn.interval, 0.81));
+        assert_eq!(spawn.count, 5);
+        assert_eq!(spawn.formation, "ellipse");
+        assert_eq!(spawn.side, Side::Left);
+        assert_eq!(harder.boss.as_deref(), Some("mothership"));
+    }
+
+    #[test]
+    fn harder_is_capped() {
+        let harder = definition().harder(100);
+        assert_eq!(harder.speed, SPEED_MAX);
+        assert_eq!(harder.spawns[0].interval, SPAWN_INTERVAL_MIN);
+    }
+
+    #[test]
+    fn waves_past_the_script_repeat_the_last_one_harder() {
+        let script = WaveScript::default();
+        let last = script.waves.len() as u32;
+        let expected = script.waves[last as usize - 1].harder(2);
+
+        let wave = script.wave(last + 2);
+        assert_eq!(wave.fire_rate, expected.fire_rate);
+        assert_eq!(wave.speed, expected.speed);
+        assert_eq!(wave.spawns.len(), expected.spawns.len());
+        assert_eq!(wave.spawns[0].count, expected.spawns[0].count);
+    }
+}